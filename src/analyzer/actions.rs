@@ -2,22 +2,28 @@
 ///
 /// This function performs the following tasks:
 /// - Runs `cargo fmt` to format the file.
-/// - Runs `cargo clippy` to check for linting issues.
+/// - Runs `cargo clippy` to check for linting issues and hands on the lints found in the file
+///   as `clippy::<lint>` findings, so they can be explained.
 /// - Analyzes the file's complexity, including cyclomatic complexity and function size.
 /// - Applies custom rules defined in a TOML file.
 /// - Checks the file's status in the Git repository, including staleness and most frequent author.
+/// - Attaches the `CODEOWNERS` owners to findings and names them with every finding and
+///   instead of the most frequent author, and warns about changed files without owner.
+/// - With `scope = "diff"`, drops all findings that do not overlap the lines changed in git.
+/// - Blames the lines of every finding and, with `only_mine`, drops findings of other authors.
 ///
 /// # Arguments
 ///
 /// * `path` - The path to the file being analyzed.
-/// * `grumpiness_level` - The level of grumpiness, which affects the tone of messages.
-/// * `max_function_size` - The maximum allowed size of a function in lines of code.
-/// * `max_cyclomatic_complexity` - The maximum allowed cyclomatic complexity of a function.
-/// * `custom_rules_path` - The path to the TOML file containing custom rules.
+/// * `config` - The merged configuration: grumpiness level, function size and complexity
+///   limits, fix mode and git settings.
+/// * `rules` - The custom rules, as loaded from the rules file.
+/// * `baseline` - Optional baseline of known findings that shall not be reported again.
 ///
 /// # Returns
 ///
-/// A `String` containing informational, warning, and error messages generated during the analysis.
+/// A `String` containing informational, warning, and error messages generated during the analysis,
+/// together with the reported findings (fixes that were already applied are stripped from them).
 ///
 /// # Errors
 ///
@@ -31,43 +37,54 @@
 ///
 /// ```rust
 /// use std::path::Path;
-/// use crate::config::GrumpinessLevel;
 ///
 /// let path = Path::new("src/main.rs");
-/// let grumpiness_level = GrumpinessLevel::High;
-/// let max_function_size = 50;
-/// let max_cyclomatic_complexity = 10;
-/// let custom_rules_path = Path::new("custom_rules.toml");
+/// let config = MergedConfig::from_sources(cli_args, None);
 ///
-/// let messages = handle_file_changes(
-///     path,
-///     &grumpiness_level,
-///     &max_function_size,
-///     &max_cyclomatic_complexity,
-///     custom_rules_path,
-/// );
+/// let rules = load_custom_rules_from_toml(&config.custom_rules)?.unwrap_or_default();
+/// let (messages, findings) = handle_file_changes(path, &config, &rules, None, None, None);
 /// println!("{}", messages);
 /// ```
-
 use std::fs;
 use std::io::{self};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 
+use crate::analyzer::adaptive::OffenseTracker;
+use crate::analyzer::baseline::Baseline;
+use crate::analyzer::codeowners::CodeOwners;
+use crate::analyzer::commit_message::lint_commit_message;
 use crate::analyzer::complexity_inspector;
 use crate::analyzer::custom_rules::*;
+use crate::analyzer::finding::{Finding, TextEdit, rules};
+use crate::analyzer::fix;
 use crate::analyzer::git;
 use crate::analyzer::messages::*;
-use crate::config::GrumpinessLevel;
+use crate::cli::MergedConfig;
+use crate::config::{AnalysisScope, GrumpinessLevel};
 use crate::{error, info, warning};
 
 pub fn handle_file_changes(
     path: &Path,
-    grumpiness_level: &GrumpinessLevel,
-    max_function_size: &u8,
-    max_cyclomatic_complexity: &u8,
-    custom_rules_path: &Path,
-) -> String {
+    config: &MergedConfig,
+    rules: &[RuleConfig],
+    baseline: Option<&Baseline>,
+    inspector: Option<&git::GitInspector>,
+    mut offenses: Option<&mut OffenseTracker>,
+) -> (String, Vec<Finding>) {
+    // `[[overrides]]` matching the file replace the global settings
+    let config = &config.for_file(&workdir_path(path));
+    let grumpiness_level = &config.grumpiness_level;
+    // A long-lived inspector keeps its caches between runs; files of other repositories
+    // (e.g. nested checkouts) get their own.
+    let discovered;
+    let inspector = match inspector.filter(|inspector| inspector.relative_path(path).is_ok()) {
+        Some(inspector) => Ok(inspector),
+        None => {
+            discovered = git::GitInspector::new(path);
+            discovered.as_ref()
+        }
+    };
     let mut info_messages = format!(
         "Detected changes in '{:?}'\n",
         extract_path_from_src(path).unwrap_or("".to_string())
@@ -83,21 +100,61 @@ pub fn handle_file_changes(
             error_messages.push_str(&format!("❌ Failed to run 'cargo fmt': {}\n", e));
         }
     };
+
+    // Computed after formatting, which moves lines around, so they match the findings
+    let hunks = match config.scope {
+        AnalysisScope::File => None,
+        AnalysisScope::Diff => match inspector
+            .map_err(ToString::to_string)
+            .and_then(|inspector| {
+                inspector
+                    .diff_hunks(path, &config.git.diff_base)
+                    .map_err(|e| e.to_string())
+            }) {
+            Ok(hunks) => Some(hunks),
+            Err(e) => {
+                error_messages.push_str(&format!(
+                    "❌ Failed to compute changed lines, analysing the whole file: {}\n",
+                    e
+                ));
+                None
+            }
+        },
+    };
+
+    let relative_path = baseline_path(path);
+    // Ignored findings and complaints are complained about more grumpily
+    let level_of = |function: Option<&str>, rule: &str| match offenses.as_deref() {
+        Some(offenses) => offenses.level(&relative_path, function, rule, grumpiness_level),
+        None => grumpiness_level.clone(),
+    };
+    let mut complaints = vec![];
+    // Clippy lints found in the file, so they can be explained like any other finding
+    let mut lint_findings = vec![];
     match run_clippy() {
         Ok((status, stderr_bytes)) => {
             let stderr = String::from_utf8_lossy(&stderr_bytes);
 
+            // Cargo reports paths relative to the directory it runs in
+            let clippy_path = &relative_path;
+
             if status.success() {
-                info_messages.push_str(clippy::success(grumpiness_level));
-            } else if match_path(path, &stderr) {
-                warning_messages.push_str(clippy::failure(grumpiness_level));
+                info_messages.push_str(&clippy::success(grumpiness_level));
+            } else if match_path(path, &stderr)
+                && hunks.as_ref().is_none_or(|hunks| {
+                    clippy_lines(&stderr, clippy_path)
+                        .iter()
+                        .any(|line| hunks.iter().any(|hunk| hunk.overlaps(*line, *line)))
+                })
+            {
+                complaints.push(rules::CLIPPY);
+                lint_findings = clippy_findings(&stderr, clippy_path);
+                if let Some(hunks) = &hunks {
+                    lint_findings.retain(|finding| finding.overlaps_any(hunks));
+                }
                 warning!(
                     "{:?}\n",
-                    extract_clippy_error_for_path(
-                        &stderr,
-                        &extract_path_from_src(path).unwrap_or("".to_string())
-                    )
-                    .unwrap_or_default()
+                    extract_clippy_error_for_path(&stderr, clippy_path).unwrap_or_default()
                 );
             }
         }
@@ -105,17 +162,18 @@ pub fn handle_file_changes(
             error_messages.push_str(&format!("❌ Failed to run 'clippy': {}\n", err));
         }
     };
-    match analyze_file_complexity(
-        path,
-        grumpiness_level,
-        max_function_size,
-        max_cyclomatic_complexity,
-    ) {
-        Ok((status, messages)) => {
-            if !status {
-                warning_messages.push_str(&messages);
-            }
-        }
+    let mut findings: Vec<Finding> = Vec::new();
+    match fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file: {}", e))
+        .and_then(|code| {
+            analyze_source_complexity_by(
+                &code,
+                &|function, rule| level_of(Some(function), rule),
+                &config.max_function_size,
+                &config.max_complexity,
+            )
+        }) {
+        Ok((_, complexity_findings)) => findings.extend(complexity_findings),
         Err(err) => {
             error_messages.push_str(&format!(
                 "❌ Failed to analyse file with custom rules: {}\n",
@@ -123,45 +181,177 @@ pub fn handle_file_changes(
             ));
         }
     };
-    match analyze_file_with_custom_rules(path, custom_rules_path) {
-        Ok((status, messages)) => {
-            if !status {
-                warning_messages.push_str(&messages.join("\n"));
-            }
-        }
+    match analyze_file_with_custom_rules(path, rules, &|rule| level_of(None, rule)) {
+        Ok((_, rule_findings)) => findings.extend(rule_findings),
         Err(err) => {
             error_messages.push_str(&format!("❌ Failed to analyse file: {}\n", err));
         }
     };
-    match git::GitInspector::new(path) {
-        Ok(tgit_inspector) => {
-            match tgit_inspector.is_file_stale(path, 7) {
-                Ok(true) => {
-                    info_messages.push_str(&git_is_stale::info(
-                        grumpiness_level,
-                    ));
+    findings.retain(|finding| config.reports(&finding.rule));
+    let total_findings = findings.len();
+    findings.retain(|finding| !baseline.is_some_and(|b| b.contains(&relative_path, finding)));
+    let known_findings = total_findings - findings.len();
+    if let Some(hunks) = &hunks {
+        let located_findings = findings.len();
+        findings.retain(|finding| finding.overlaps_any(hunks));
+        let untouched_findings = located_findings - findings.len();
+        if untouched_findings > 0 {
+            info_messages.push_str(&format!(
+                "✂️ {} finding(s) outside the changed lines hidden\n",
+                untouched_findings
+            ));
+        }
+    }
+    if config.git_integration && (config.git.only_mine || config.git.blame_findings) {
+        match inspector
+            .map_err(ToString::to_string)
+            .and_then(|inspector| {
+                blame_findings(inspector, path, &mut findings, config.git.only_mine)
+                    .map_err(|e| e.to_string())
+            }) {
+            Ok(0) => (),
+            Ok(foreign_findings) => {
+                info_messages.push_str(&format!(
+                    "🙋 {} finding(s) by other authors hidden\n",
+                    foreign_findings
+                ));
+            }
+            Err(e) => {
+                error_messages.push_str(&format!("❌ Failed to blame findings: {}\n", e));
+            }
+        }
+    }
+    let owners = match inspector {
+        Ok(inspector) if config.git_integration && config.git.codeowners => {
+            match code_owners(inspector, path) {
+                Ok(owners) => owners,
+                Err(e) => {
+                    error_messages.push_str(&format!("❌ Failed to read CODEOWNERS: {}\n", e));
+                    None
                 }
+            }
+        }
+        _ => None,
+    };
+    if let Some(owners) = &owners {
+        findings
+            .iter_mut()
+            .for_each(|finding| finding.owners = owners.clone());
+    }
+    if let (true, Ok(inspector)) = (config.git_integration, inspector) {
+        if config.git.check_stale {
+            match inspector.is_file_stale(path, config.git.stale_days) {
+                Ok(true) => complaints.push(rules::GIT_IS_STALE),
                 Ok(false) => (),
                 Err(e) => {
                     error_messages
                         .push_str(&format!("❌ Failed to check if file is stale: {}\n", e));
                 }
             }
-            match tgit_inspector.most_frequent_author(path) {
-                Ok(author) => {
-                    info_messages.push_str(&git_most_frequent_author::info(
-                        grumpiness_level,
-                        author.as_deref().unwrap_or(""),
-                    ));
+        }
+        if owners.as_ref().is_some_and(|owners| owners.is_empty())
+            && inspector.is_file_changed(path).unwrap_or(false)
+        {
+            complaints.push(rules::CODEOWNERS);
+        }
+    }
+    let complaint_levels: Vec<GrumpinessLevel> =
+        complaints.iter().map(|rule| level_of(None, rule)).collect();
+    if let Some(offenses) = offenses.as_deref_mut()
+        && offenses.record(&relative_path, &findings, &complaints)
+        && let Err(e) = offenses.save(Path::new(&config.offenses_file))
+    {
+        error_messages.push_str(&format!("❌ Failed to remember offenses: {}\n", e));
+    }
+    for (rule, level) in complaints.iter().zip(&complaint_levels) {
+        match *rule {
+            rules::CLIPPY => warning_messages.push_str(&clippy::failure(level)),
+            rules::GIT_IS_STALE => info_messages.push_str(&git_is_stale::info(level)),
+            rules::CODEOWNERS => {
+                warning_messages.push_str(&codeowners::unowned(level));
+                warning_messages.push('\n');
+            }
+            _ => (),
+        }
+    }
+    for finding in &findings {
+        warning_messages.push_str(&finding.message);
+        warning_messages.push('\n');
+        if let Some((level, repeats)) = offenses.as_deref().and_then(|offenses| {
+            offenses.ignored(
+                &relative_path,
+                finding.function.as_deref(),
+                &finding.rule,
+                grumpiness_level,
+            )
+        }) {
+            warning_messages.push_str(&repeat_offense::ignored(&level, repeats));
+            warning_messages.push('\n');
+        }
+        if !finding.owners.is_empty() {
+            warning_messages.push_str(&codeowners::finding_owners(
+                grumpiness_level,
+                &finding.owners.join(", "),
+            ));
+            warning_messages.push('\n');
+        }
+        if let Some(author) = &finding.last_touched {
+            warning_messages.push_str(&blame::last_touched(
+                grumpiness_level,
+                &author.name,
+                &author.age(),
+            ));
+            warning_messages.push('\n');
+        }
+    }
+    if known_findings > 0 {
+        info_messages.push_str(&format!(
+            "🙈 {} known finding(s) hidden by the baseline\n",
+            known_findings
+        ));
+    }
+    if config.fix && findings.iter().any(Finding::is_fixable) {
+        let edits: Vec<TextEdit> = findings.iter().flat_map(|f| f.edits.clone()).collect();
+        match fix::fix_file(path, &edits) {
+            Ok(count) => {
+                info_messages.push_str(&format!("🔧 Applied {} fix(es)\n", count));
+                findings.iter_mut().for_each(|f| f.edits.clear());
+            }
+            Err(e) => {
+                error_messages.push_str(&format!("❌ Failed to apply fixes: {}\n", e));
+            }
+        }
+    }
+    if config.git_integration
+        && (config.git.check_stale || config.git.report_author || config.git.codeowners)
+    {
+        match inspector {
+            Ok(tgit_inspector) => {
+                if let Some(owners) = owners.as_ref().filter(|owners| !owners.is_empty()) {
+                    info_messages
+                        .push_str(&codeowners::owned_by(grumpiness_level, &owners.join(", ")));
+                    info_messages.push('\n');
                 }
-                Err(e) => {
-                    error_messages
-                        .push_str(&format!("❌ Failed to get most frequent author: {}\n", e));
+                if config.git.report_author && owners.as_ref().is_none_or(|o| o.is_empty()) {
+                    match tgit_inspector.most_frequent_author(path) {
+                        Ok(author) => {
+                            info_messages.push_str(&git_most_frequent_author::info(
+                                grumpiness_level,
+                                author.as_deref().unwrap_or(""),
+                            ));
+                        }
+                        Err(e) => {
+                            error_messages.push_str(&format!(
+                                "❌ Failed to get most frequent author: {}\n",
+                                e
+                            ));
+                        }
+                    }
                 }
             }
-        }
-        Err(e) => {
-            error_messages.push_str(&format!("❌ Failed to create GitInspector: {}\n", e));
+            Err(e) => {
+                error_messages.push_str(&format!("❌ Failed to create GitInspector: {}\n", e));
+            }
         }
     }
 
@@ -181,11 +371,84 @@ pub fn handle_file_changes(
         }
     }
 
-    println!("Generated messages: {}", info_messages);
-    println!("Generated messages: {}", warning_messages);
-    println!("Generated messages: {}", error_messages);
+    // Already reported by clippy itself, only handed on to be explained
+    findings.extend(lint_findings);
+    (
+        format!("{}{}{}", info_messages, warning_messages, error_messages),
+        findings,
+    )
+}
 
-    info_messages + &warning_messages + &error_messages
+/// Owners of `path` according to the repository's `CODEOWNERS`: `None` without a
+/// `CODEOWNERS` file, empty if the file is not owned by anybody.
+fn code_owners(inspector: &git::GitInspector, path: &Path) -> Result<Option<Vec<String>>, String> {
+    let workdir = inspector.workdir().map_err(|e| e.to_string())?;
+    let Some(code_owners) = CodeOwners::discover(&workdir)? else {
+        return Ok(None);
+    };
+    let relative_path = inspector.relative_path(path).map_err(|e| e.to_string())?;
+    Ok(Some(code_owners.owners_of(&relative_path).to_vec()))
+}
+
+/// Attaches the last author of their lines to all located findings. With `only_mine`, the
+/// findings of other authors than the current git user are removed; their count is returned.
+fn blame_findings(
+    inspector: &git::GitInspector,
+    path: &Path,
+    findings: &mut Vec<Finding>,
+    only_mine: bool,
+) -> Result<usize, git2::Error> {
+    let file_blame = inspector.blame_working_copy(path)?;
+    for finding in findings.iter_mut() {
+        if let Some(line) = finding.line {
+            finding.last_touched = file_blame
+                .last_touched(line, finding.end_line.unwrap_or(line))
+                .cloned();
+        }
+    }
+    if !only_mine {
+        return Ok(0);
+    }
+    let me = inspector.current_user()?;
+    let total_findings = findings.len();
+    findings.retain(|finding| {
+        finding
+            .last_touched
+            .as_ref()
+            .is_none_or(|author| author.is_by(&me))
+    });
+    Ok(total_findings - findings.len())
+}
+
+/// Handles changes of the commit message being edited (`.git/COMMIT_EDITMSG`) by checking it
+/// against the `[commit_msg]` conventions.
+///
+/// Returns the generated messages together with the findings, like `handle_file_changes`.
+pub fn handle_commit_message(path: &Path, config: &MergedConfig) -> (String, Vec<Finding>) {
+    let mut messages = String::from("Detected changes in the commit message\n");
+    let findings = match fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file: {}", e))
+        .and_then(|raw| lint_commit_message(&raw, &config.commit_msg, &config.grumpiness_level))
+    {
+        Ok(findings) => findings,
+        Err(e) => {
+            let message = format!("❌ Failed to check commit message: {}", e);
+            error!("{}", message);
+            return (messages + message.as_str(), vec![]);
+        }
+    };
+
+    if findings.is_empty() {
+        let passed = commit_message::passed(&config.grumpiness_level);
+        info!("{}", passed);
+        messages.push_str(&passed);
+    }
+    for finding in &findings {
+        warning!("{}", finding.message);
+        messages.push_str(&finding.message);
+        messages.push('\n');
+    }
+    (messages, findings)
 }
 
 fn run_cmd(mut cmd: Command) -> io::Result<(ExitStatus, Vec<u8>)> {
@@ -217,6 +480,23 @@ fn run_clippy() -> io::Result<(ExitStatus, Vec<u8>)> {
     run_cmd(cmd)
 }
 
+/// `path` relative to the working directory, which `[[overrides]]` paths are relative to.
+pub(crate) fn workdir_path(path: &Path) -> PathBuf {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// Path under which findings of `path` are recorded in a baseline: relative to the working
+/// directory like the paths the `baseline` command walks (e.g. `src/lib.rs`), so that the
+/// absolute paths reported by the watcher key the same.
+pub(crate) fn baseline_path(path: &Path) -> String {
+    workdir_path(path).to_string_lossy().replace('\\', "/")
+}
+
 fn extract_path_from_src(path: &Path) -> Option<String> {
     let delimiter = "src/".to_string();
     path.to_str()
@@ -226,6 +506,9 @@ fn extract_path_from_src(path: &Path) -> Option<String> {
 }
 
 fn match_path(path: &Path, std_err: &str) -> bool {
+    if std_err.contains(&format!("--> {}:", baseline_path(path))) {
+        return true;
+    }
     if let Some(relative_path) = extract_path_from_src(&path) {
         return std_err.contains(&format!("--> {}", relative_path))
             || std_err.contains(&format!("--> {}", path.display()));
@@ -234,6 +517,47 @@ fn match_path(path: &Path, std_err: &str) -> bool {
     }
 }
 
+/// Lines of all clippy diagnostics pointing into `path` (e.g. `--> src/lib.rs:12:5`).
+fn clippy_lines(stderr: &str, path: &str) -> Vec<usize> {
+    let location = format!("{}:", path);
+    stderr
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("--> "))
+        .filter_map(|target| target.strip_prefix(&location))
+        .filter_map(|position| position.split(':').next()?.parse().ok())
+        .collect()
+}
+
+/// One finding per clippy lint reported in `path`, with the rule `clippy::<lint>` and the
+/// headline of the diagnostic as message. The lint name is read from the documentation link
+/// clippy adds to every diagnostic.
+fn clippy_findings(stderr: &str, path: &str) -> Vec<Finding> {
+    let location = format!("{}:", path);
+    let mut findings = vec![];
+    let mut headline = "";
+    let mut line = None;
+    for text in stderr.lines() {
+        if !text.starts_with(' ') && text.contains(": ") {
+            headline = text;
+            line = None;
+        } else if let Some(target) = text.trim_start().strip_prefix("--> ") {
+            line = line.or_else(|| {
+                target
+                    .strip_prefix(&location)?
+                    .split(':')
+                    .next()?
+                    .parse()
+                    .ok()
+            });
+        } else if let (Some(at), Some((_, lint))) = (line, text.split_once("index.html#")) {
+            let rule = format!("clippy::{}", lint.trim());
+            findings.push(Finding::new(&rule, None, headline.to_string()).at_line(at));
+            line = None;
+        }
+    }
+    findings
+}
+
 fn extract_clippy_error_for_path<'a>(stderr: &'a str, path: &str) -> Option<&'a str> {
     let mut lines: std::iter::Peekable<std::str::Lines<'_>> = stderr.lines().peekable();
     let mut collecting = false;
@@ -266,55 +590,139 @@ fn extract_clippy_error_for_path<'a>(stderr: &'a str, path: &str) -> Option<&'a
     }
 }
 
-fn analyze_file_complexity(
+pub(crate) fn analyze_file_complexity(
     path: &Path,
     grumpiness_level: &GrumpinessLevel,
     max_function_size: &u8,
     max_cyclomatic_complexity: &u8,
-) -> Result<(bool, String), String> {
+) -> Result<(bool, Vec<Finding>), String> {
+    let code = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    analyze_source_complexity(
+        &code,
+        grumpiness_level,
+        max_function_size,
+        max_cyclomatic_complexity,
+    )
+}
+
+pub(crate) fn analyze_source_complexity(
+    code: &str,
+    grumpiness_level: &GrumpinessLevel,
+    max_function_size: &u8,
+    max_cyclomatic_complexity: &u8,
+) -> Result<(bool, Vec<Finding>), String> {
+    analyze_source_complexity_by(
+        code,
+        &|_, _| grumpiness_level.clone(),
+        max_function_size,
+        max_cyclomatic_complexity,
+    )
+}
+
+/// Like `analyze_source_complexity`, with the grumpiness chosen per function qualified name
+/// and rule by `level_of`.
+fn analyze_source_complexity_by(
+    code: &str,
+    level_of: &dyn Fn(&str, &str) -> GrumpinessLevel,
+    max_function_size: &u8,
+    max_cyclomatic_complexity: &u8,
+) -> Result<(bool, Vec<Finding>), String> {
     let mut successful = true;
-    let mut messages = String::new();
+    let mut findings = Vec::new();
 
-    let code = fs::read_to_string(path).expect("Failed to read file");
-    let syntax = syn::parse_file(&code).expect("Syntax error");
+    let syntax = syn::parse_file(code).map_err(|e| format!("Syntax error: {}", e))?;
 
     let metrics = complexity_inspector::analyze_file(&syntax);
     for m in metrics {
         if m.cyclomatic_complexity as u8 > *max_cyclomatic_complexity {
-            messages.push_str(&complexity::warning(
-                &grumpiness_level,
-                &m.name,
-                m.cyclomatic_complexity,
-                *max_cyclomatic_complexity,
-            ));
-            messages.push('\n');
+            findings.push(
+                Finding::new(
+                    rules::COMPLEXITY,
+                    Some(&m.qualified_name),
+                    complexity::warning(
+                        &level_of(&m.qualified_name, rules::COMPLEXITY),
+                        &m.name,
+                        m.cyclomatic_complexity,
+                        *max_cyclomatic_complexity,
+                    ),
+                )
+                .at_line(m.start_line)
+                .until_line(m.end_line),
+            );
             successful = false;
         }
         if m.lines_of_code as u8 > *max_function_size {
-            messages.push_str(&function_size::warning(
-                &grumpiness_level,
-                &m.name,
-                m.lines_of_code,
-                *max_function_size,
-            ));
-            messages.push('\n');
+            findings.push(
+                Finding::new(
+                    rules::FUNCTION_SIZE,
+                    Some(&m.qualified_name),
+                    function_size::warning(
+                        &level_of(&m.qualified_name, rules::FUNCTION_SIZE),
+                        &m.name,
+                        m.lines_of_code,
+                        *max_function_size,
+                    ),
+                )
+                .at_line(m.start_line)
+                .until_line(m.end_line),
+            );
             successful = false;
         }
     }
-    Ok((successful, messages))
+    Ok((successful, findings))
 }
 
-fn analyze_file_with_custom_rules(
+/// Applies the custom `rules` to a file, with the grumpiness of the messages chosen per rule
+/// by `level_of`.
+pub(crate) fn analyze_file_with_custom_rules(
     path: &Path,
-    custom_rules_path: &Path,
-) -> Result<(bool, Vec<String>), String> {
-    let code = fs::read_to_string(path).expect("Failed to read file");
-    match load_custom_rules_from_toml(custom_rules_path.to_str().unwrap()) {
-        Ok(Some(rules)) => apply_rules(rules, &code),
-        Ok(None) => {
-            info!("No custom rules found, skipping custom rules analysis.");
-            Ok((true, vec![])) // No rules means no issues
-        },
-        Err(e) => return Err(format!("Failed to load custom rules: {}", e)),
+    rules: &[RuleConfig],
+    level_of: &dyn Fn(&str) -> GrumpinessLevel,
+) -> Result<(bool, Vec<Finding>), String> {
+    if rules.is_empty() {
+        return Ok((true, vec![])); // No rules means no issues
+    }
+    let code = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    apply_rules_by(rules, &code, level_of)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clippy_lines() {
+        let stderr = "warning: unused variable\n  --> src/lib.rs:12:9\n   |\nerror: oops\n --> src/main.rs:3:1\n  --> src/lib.rs:40:5\n";
+        assert_eq!(clippy_lines(stderr, "src/lib.rs"), vec![12, 40]);
+        assert!(clippy_lines(stderr, "src/li").is_empty());
+    }
+
+    #[test]
+    fn test_clippy_findings() {
+        let stderr = "warning: unneeded `return` statement\n  --> src/lib.rs:12:9\n   |\n   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#needless_return\n   = note: `#[warn(clippy::needless_return)]` on by default\n\nwarning: this is elsewhere\n --> src/main.rs:3:1\n   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#len_zero\n\nwarning: unneeded `return` statement\n  --> src/lib.rs:40:5\n   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#needless_return\n";
+        let findings = clippy_findings(stderr, "src/lib.rs");
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| (f.rule.as_str(), f.line))
+                .collect::<Vec<_>>(),
+            [
+                ("clippy::needless_return", Some(12)),
+                ("clippy::needless_return", Some(40))
+            ]
+        );
+        assert_eq!(findings[0].message, "warning: unneeded `return` statement");
+    }
+
+    #[test]
+    fn test_baseline_path_is_relative_to_working_directory() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(baseline_path(&cwd.join("src/lib.rs")), "src/lib.rs");
+        assert_eq!(baseline_path(Path::new("./src/lib.rs")), "src/lib.rs");
+        assert_eq!(baseline_path(&cwd.join("build.rs")), "build.rs");
+        assert_eq!(
+            baseline_path(Path::new("/home/me/src/proj/src/lib.rs")),
+            "/home/me/src/proj/src/lib.rs"
+        );
     }
 }
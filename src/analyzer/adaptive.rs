@@ -0,0 +1,218 @@
+//! Adaptive grumpiness.
+//!
+//! Remembers for how many analyses in a row each finding has been reported, keyed like the
+//! baseline by file path, function qualified name and rule id. The longer a problem is
+//! ignored, the grumpier Clippy gets about it; once it is fixed, its count is dropped and
+//! Clippy calms down again. The counts are kept in a file so they survive restarts, by
+//! default next to the baseline file (see [`default_offenses_file`]).
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::analyzer::finding::Finding;
+use crate::config::GrumpinessLevel;
+
+pub const DEFAULT_OFFENSES_FILE: &str = ".grumpyclippy-offenses.json";
+
+/// Number of repeated reports after which Clippy gets one level grumpier
+pub const REPEATS_PER_ESCALATION: u32 = 2;
+
+/// Where offenses are kept unless configured: next to the baseline file if there is one,
+/// else in the working directory.
+pub fn default_offenses_file(baseline_file: Option<&str>) -> String {
+    match baseline_file.and_then(|baseline| Path::new(baseline).parent()) {
+        Some(dir) => dir
+            .join(DEFAULT_OFFENSES_FILE)
+            .to_string_lossy()
+            .into_owned(),
+        None => DEFAULT_OFFENSES_FILE.to_string(),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OffenseTracker {
+    /// Analyses in a row each finding was reported in, by file and finding key
+    offenses: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl OffenseTracker {
+    /// Reads the tracked offenses; a missing file means a clean record.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("'{}' seems to be invalid: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize offenses: {}", e))?;
+        fs::write(path, content + "\n")
+            .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+    }
+
+    /// How often the finding of `rule` in `function` was reported in a row so far.
+    pub fn repeats(&self, path: &str, function: Option<&str>, rule: &str) -> u32 {
+        self.offenses
+            .get(path)
+            .and_then(|offenses| offenses.get(&key(function, rule)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Grumpiness for reporting the finding of `rule` in `function` once more, escalated from
+    /// `base` by one level per [`REPEATS_PER_ESCALATION`] repeated reports.
+    pub fn level(
+        &self,
+        path: &str,
+        function: Option<&str>,
+        rule: &str,
+        base: &GrumpinessLevel,
+    ) -> GrumpinessLevel {
+        escalated(base, self.repeats(path, function, rule))
+    }
+
+    /// Grumpiness the finding of `rule` in `function` was last reported with and how often it
+    /// has been reported in a row, once it was ignored for more than [`REPEATS_PER_ESCALATION`]
+    /// analyses. To be asked after [`OffenseTracker::record`]ing the analysis.
+    pub fn ignored(
+        &self,
+        path: &str,
+        function: Option<&str>,
+        rule: &str,
+        base: &GrumpinessLevel,
+    ) -> Option<(GrumpinessLevel, u32)> {
+        let repeats = self.repeats(path, function, rule);
+        (repeats > REPEATS_PER_ESCALATION).then(|| (escalated(base, repeats - 1), repeats))
+    }
+
+    /// Records the findings and the file level complaints (rule ids, e.g. a clippy failure)
+    /// reported for `path` in this analysis. Findings reported again count one more repeat,
+    /// findings no longer reported are forgiven. Returns whether the record changed.
+    pub fn record(&mut self, path: &str, findings: &[Finding], complaints: &[&str]) -> bool {
+        let previous = self.offenses.remove(path).unwrap_or_default();
+        let current: BTreeMap<String, u32> = findings
+            .iter()
+            .map(|finding| key(finding.function.as_deref(), &finding.rule))
+            .chain(complaints.iter().map(|rule| key(None, rule)))
+            .map(|key| {
+                let repeats = previous.get(&key).copied().unwrap_or(0) + 1;
+                (key, repeats)
+            })
+            .collect();
+        let changed = current != previous;
+        if !current.is_empty() {
+            self.offenses.insert(path.to_string(), current);
+        }
+        changed
+    }
+}
+
+/// `base` escalated by one level per [`REPEATS_PER_ESCALATION`] earlier reports
+fn escalated(base: &GrumpinessLevel, earlier_reports: u32) -> GrumpinessLevel {
+    base.escalated(earlier_reports / REPEATS_PER_ESCALATION)
+}
+
+fn key(function: Option<&str>, rule: &str) -> String {
+    match function {
+        Some(function) => format!("{}::{}", function, rule),
+        None => rule.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::finding::rules;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_escalates_while_ignored_and_calms_down_when_fixed() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join(DEFAULT_OFFENSES_FILE);
+        let finding = Finding::new(rules::COMPLEXITY, Some("parse"), "too complex".into());
+        let level = |tracker: &OffenseTracker| {
+            tracker.level(
+                "src/lib.rs",
+                Some("parse"),
+                rules::COMPLEXITY,
+                &GrumpinessLevel::Mild,
+            )
+        };
+
+        let mut tracker = OffenseTracker::from_file(&file).unwrap();
+        let mut levels = vec![];
+        let mut complaints = vec![];
+        for _ in 0..5 {
+            levels.push(level(&tracker));
+            assert!(tracker.record("src/lib.rs", std::slice::from_ref(&finding), &[]));
+            complaints.push(tracker.ignored(
+                "src/lib.rs",
+                Some("parse"),
+                rules::COMPLEXITY,
+                &GrumpinessLevel::Mild,
+            ));
+        }
+        assert_eq!(
+            levels,
+            [
+                GrumpinessLevel::Mild,
+                GrumpinessLevel::Mild,
+                GrumpinessLevel::Sarcastic,
+                GrumpinessLevel::Sarcastic,
+                GrumpinessLevel::Rude,
+            ]
+        );
+        // The complaint about the ignored finding is as grumpy as the finding itself
+        assert_eq!(
+            complaints,
+            [
+                None,
+                None,
+                Some((GrumpinessLevel::Sarcastic, 3)),
+                Some((GrumpinessLevel::Sarcastic, 4)),
+                Some((GrumpinessLevel::Rude, 5)),
+            ]
+        );
+
+        tracker.save(&file).unwrap();
+        let mut tracker = OffenseTracker::from_file(&file).unwrap();
+        assert_eq!(level(&tracker), GrumpinessLevel::Rude);
+
+        assert!(tracker.record("src/lib.rs", &[], &[]));
+        assert_eq!(level(&tracker), GrumpinessLevel::Mild);
+        assert_eq!(tracker, OffenseTracker::default());
+        assert!(!tracker.record("src/lib.rs", &[], &[]));
+    }
+
+    #[test]
+    fn test_file_level_complaints_escalate() {
+        let mut tracker = OffenseTracker::default();
+        for _ in 0..3 {
+            tracker.record("src/lib.rs", &[], &[rules::CLIPPY]);
+        }
+        assert_eq!(
+            tracker.level("src/lib.rs", None, rules::CLIPPY, &GrumpinessLevel::Mild),
+            GrumpinessLevel::Sarcastic
+        );
+    }
+
+    #[test]
+    fn test_default_offenses_file_is_next_to_the_baseline() {
+        assert_eq!(default_offenses_file(None), DEFAULT_OFFENSES_FILE);
+        assert_eq!(
+            default_offenses_file(Some("known.json")),
+            DEFAULT_OFFENSES_FILE
+        );
+        assert_eq!(
+            default_offenses_file(Some("ci/known.json")),
+            Path::new("ci")
+                .join(DEFAULT_OFFENSES_FILE)
+                .to_string_lossy()
+        );
+    }
+}
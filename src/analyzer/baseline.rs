@@ -0,0 +1,143 @@
+//! Baseline of already known findings.
+//!
+//! A baseline file records the findings present at the time it was created so that
+//! only newly introduced problems are reported afterwards. Entries are keyed by file
+//! path, function qualified name and rule id; line numbers are not part of the key.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::finding::Finding;
+
+pub const DEFAULT_BASELINE_FILE: &str = ".grumpyclippy-baseline.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub path: String,
+    pub function: Option<String>,
+    pub rule: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    findings: BTreeSet<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn from_file(path: &Path) -> Result<Self, BaselineError> {
+        if !path.exists() {
+            return Err(BaselineError::FileNotFound(path.to_path_buf()));
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| BaselineError::InvalidFile(path.to_path_buf(), e.to_string()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| BaselineError::InvalidFile(path.to_path_buf(), e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| BaselineError::WriteFailed(path.to_path_buf(), e.to_string()))?;
+        fs::write(path, content + "\n")
+            .map_err(|e| BaselineError::WriteFailed(path.to_path_buf(), e.to_string()))
+    }
+
+    pub fn insert(&mut self, path: &str, finding: &Finding) {
+        self.findings.insert(entry(path, finding));
+    }
+
+    pub fn contains(&self, path: &str, finding: &Finding) -> bool {
+        self.findings.contains(&entry(path, finding))
+    }
+
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+}
+
+fn entry(path: &str, finding: &Finding) -> BaselineEntry {
+    BaselineEntry {
+        path: path.to_string(),
+        function: finding.function.clone(),
+        rule: finding.rule.clone(),
+    }
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    FileNotFound(PathBuf),
+    InvalidFile(PathBuf, String),
+    WriteFailed(PathBuf, String),
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BaselineError::FileNotFound(filepath) => {
+                write!(f, "Baseline '{}' could not be found", filepath.display())
+            }
+            BaselineError::InvalidFile(filepath, error) => {
+                write!(
+                    f,
+                    "Baseline '{}' seems to be invalid. Error: {}",
+                    filepath.display(),
+                    error
+                )
+            }
+            BaselineError::WriteFailed(filepath, error) => {
+                write!(
+                    f,
+                    "Baseline '{}' could not be written. Error: {}",
+                    filepath.display(),
+                    error
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn finding(function: &str, rule: &str, message: &str) -> Finding {
+        Finding::new(rule, Some(function), message.to_string())
+    }
+
+    #[test]
+    fn test_contains_ignores_message() {
+        let mut baseline = Baseline::default();
+        baseline.insert("src/lib.rs", &finding("parse", "complexity", "(12 > 10)"));
+
+        assert!(baseline.contains("src/lib.rs", &finding("parse", "complexity", "(14 > 10)")));
+        assert!(!baseline.contains("src/lib.rs", &finding("parse", "function_size", "")));
+        assert!(!baseline.contains("src/main.rs", &finding("parse", "complexity", "")));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("baseline.json");
+        let mut baseline = Baseline::default();
+        baseline.insert("src/lib.rs", &finding("Lexer::next", "complexity", ""));
+        baseline.insert(
+            "src/lib.rs",
+            &Finding::new("no_todo_comments", None, String::new()),
+        );
+        baseline.save(&file_path).unwrap();
+
+        let loaded = Baseline::from_file(&file_path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.contains("src/lib.rs", &finding("Lexer::next", "complexity", "")));
+    }
+
+    #[test]
+    fn test_missing_baseline_file() {
+        assert!(matches!(
+            Baseline::from_file(Path::new("baseline_does_not_exist.json")),
+            Err(BaselineError::FileNotFound(_))
+        ));
+    }
+}
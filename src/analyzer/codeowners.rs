@@ -0,0 +1,149 @@
+//! `CODEOWNERS` support: which team owns a file.
+//!
+//! Like GitHub, the first file found among `.github/CODEOWNERS`, `CODEOWNERS` and
+//! `docs/CODEOWNERS` is used, and the last matching pattern wins. Patterns follow the
+//! gitignore rules GitHub supports: `*`, `**`, `?`, a leading `/` or an inner `/` anchor the
+//! pattern at the repository root, and a directory pattern covers everything below it.
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+/// Locations searched for a `CODEOWNERS` file, in order of precedence.
+pub const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+pub struct CodeOwners {
+    rules: Vec<(Regex, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Loads the `CODEOWNERS` file of the repository at `workdir`, if there is one.
+    pub fn discover(workdir: &Path) -> Result<Option<Self>, String> {
+        let Some(path) = CODEOWNERS_LOCATIONS
+            .iter()
+            .map(|location| workdir.join(location))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        Self::parse(&content)
+            .map(Some)
+            .map_err(|e| format!("Invalid '{}': {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next().unwrap_or_default();
+            let owners = parts
+                .take_while(|owner| !owner.starts_with('#'))
+                .map(String::from)
+                .collect();
+            let regex = Regex::new(&pattern_to_regex(pattern))
+                .map_err(|e| format!("line {}: invalid pattern '{}': {}", index + 1, pattern, e))?;
+            rules.push((regex, owners));
+        }
+        Ok(CodeOwners { rules })
+    }
+
+    /// Owners of `relative_path` (relative to the repository root); empty if nobody owns it.
+    pub fn owners_of(&self, relative_path: &Path) -> &[String] {
+        let path = relative_path.to_string_lossy().replace('\\', "/");
+        self.rules
+            .iter()
+            .rev()
+            .find(|(regex, _)| regex.is_match(&path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Regex matching the paths (relative to the repository root, with `/` as separator) a
+/// gitignore style pattern covers. Also used for the paths of `[[overrides]]` in the config.
+pub(crate) fn pattern_to_regex(pattern: &str) -> String {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    // A pattern naming a directory covers everything below it
+    regex.push_str(if directory_only { "/.*$" } else { "(?:/.*)?$" });
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const CODEOWNERS: &str = r#"
+# Default owners
+*                   @org/everyone
+*.rs                @org/rustaceans
+/docs/              @org/writers
+src/analyzer/**/git.rs @org/git-team @alice # inline comment
+build/logs/
+"#;
+
+    fn owners(path: &str) -> Vec<String> {
+        CodeOwners::parse(CODEOWNERS)
+            .unwrap()
+            .owners_of(Path::new(path))
+            .to_vec()
+    }
+
+    #[test]
+    fn test_last_matching_pattern_wins() {
+        assert_eq!(owners("README.md"), vec!["@org/everyone"]);
+        assert_eq!(owners("src/deep/lib.rs"), vec!["@org/rustaceans"]);
+        assert_eq!(owners("docs/guide/intro.rs"), vec!["@org/writers"]);
+        assert_eq!(owners("src/docs/intro.md"), vec!["@org/everyone"]);
+        assert_eq!(
+            owners("src/analyzer/git.rs"),
+            vec!["@org/git-team", "@alice"]
+        );
+        assert_eq!(
+            owners("src/analyzer/x/y/git.rs"),
+            vec!["@org/git-team", "@alice"]
+        );
+        assert!(owners("build/logs/today.log").is_empty());
+    }
+
+    #[test]
+    fn test_discover_prefers_github_directory() {
+        let dir = tempdir().unwrap();
+        assert!(CodeOwners::discover(dir.path()).unwrap().is_none());
+
+        fs::write(dir.path().join("CODEOWNERS"), "* @root\n").unwrap();
+        fs::create_dir(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/CODEOWNERS"), "* @github\n").unwrap();
+        let codeowners = CodeOwners::discover(dir.path()).unwrap().unwrap();
+        assert_eq!(codeowners.owners_of(Path::new("lib.rs")), ["@github"]);
+    }
+}
@@ -0,0 +1,189 @@
+//! Checks commit messages against the conventions of the `[commit_msg]` config section:
+//! conventional commit type prefix, subject length, body wrapping and a referenced issue key.
+use regex::Regex;
+
+use crate::analyzer::finding::{Finding, rules};
+use crate::analyzer::messages::commit_message;
+use crate::cli::CommitMsgConfig;
+use crate::config::GrumpinessLevel;
+
+/// Line git puts above the diff of `git commit --verbose`; everything below is dropped.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Subjects generated by git itself, which are exempt from the type check.
+const GENERATED_PREFIXES: [&str; 4] = ["Merge ", "Revert \"", "fixup! ", "squash! "];
+
+/// Removes comment lines and the verbose diff, the way git cleans up the message.
+pub fn strip_comments(raw: &str) -> String {
+    raw.lines()
+        .take_while(|line| *line != SCISSORS)
+        .filter(|line| !line.starts_with('#'))
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Lints a raw commit message (as found in `.git/COMMIT_EDITMSG`).
+///
+/// Findings carry the (1-based) line of the cleaned up message they refer to.
+pub fn lint_commit_message(
+    raw: &str,
+    config: &CommitMsgConfig,
+    grumpiness_level: &GrumpinessLevel,
+) -> Result<Vec<Finding>, String> {
+    let message = strip_comments(raw);
+    let mut lines = message.lines();
+    let Some(subject) = lines.next() else {
+        return Ok(vec![]);
+    };
+    let mut findings = Vec::new();
+
+    if !config.types.is_empty()
+        && !GENERATED_PREFIXES
+            .iter()
+            .any(|prefix| subject.starts_with(prefix))
+    {
+        let types = config
+            .types
+            .iter()
+            .map(|t| regex::escape(t))
+            .collect::<Vec<_>>()
+            .join("|");
+        let prefix = Regex::new(&format!(r"^({})(\([^)]+\))?!?: \S", types))
+            .map_err(|e| format!("Invalid commit types: {}", e))?;
+        if !prefix.is_match(subject) {
+            findings.push(
+                Finding::new(
+                    rules::COMMIT_TYPE,
+                    None,
+                    commit_message::missing_type(grumpiness_level, &config.types.join(", ")),
+                )
+                .at_line(1),
+            );
+        }
+    }
+
+    let subject_length = subject.chars().count();
+    if subject_length > config.max_subject_length {
+        findings.push(
+            Finding::new(
+                rules::SUBJECT_LENGTH,
+                None,
+                commit_message::subject_too_long(
+                    grumpiness_level,
+                    subject_length,
+                    config.max_subject_length,
+                ),
+            )
+            .at_line(1),
+        );
+    }
+
+    if lines.next().is_some_and(|line| !line.is_empty()) {
+        findings.push(
+            Finding::new(
+                rules::BODY_WRAP,
+                None,
+                commit_message::missing_blank_line(grumpiness_level),
+            )
+            .at_line(2),
+        );
+    }
+    for (index, line) in message.lines().enumerate().skip(2) {
+        let length = line.chars().count();
+        // Long URLs and other unbreakable words cannot be wrapped
+        if length > config.max_body_line_length && line.trim().contains(' ') {
+            findings.push(
+                Finding::new(
+                    rules::BODY_WRAP,
+                    None,
+                    commit_message::body_line_too_long(
+                        grumpiness_level,
+                        index + 1,
+                        length,
+                        config.max_body_line_length,
+                    ),
+                )
+                .at_line(index + 1),
+            );
+        }
+    }
+
+    if let Some(issue_key) = &config.issue_key {
+        let pattern = Regex::new(issue_key)
+            .map_err(|e| format!("Invalid issue key pattern '{}': {}", issue_key, e))?;
+        if !pattern.is_match(&message) {
+            findings.push(Finding::new(
+                rules::ISSUE_KEY,
+                None,
+                commit_message::missing_issue_key(grumpiness_level, issue_key),
+            ));
+        }
+    }
+
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CommitMsgConfig {
+        CommitMsgConfig {
+            types: vec!["feat".into(), "fix".into()],
+            max_subject_length: 30,
+            max_body_line_length: 20,
+            issue_key: None,
+        }
+    }
+
+    fn rules_of(raw: &str, config: &CommitMsgConfig) -> Vec<(String, Option<usize>)> {
+        lint_commit_message(raw, config, &GrumpinessLevel::Mild)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.rule, f.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_message() {
+        let raw = "feat(ui)!: add button\n\nShort body line\nhttps://example.com/a/very/long/url\n# Please enter the commit message\n";
+        assert!(rules_of(raw, &config()).is_empty());
+        assert!(rules_of("Merge branch 'main'", &config()).is_empty());
+        assert!(rules_of("# only comments\n", &config()).is_empty());
+    }
+
+    #[test]
+    fn test_type_subject_and_body_violations() {
+        let raw = "added a very long subject without type\nbody without blank line\n\nthis body line is way too long\n";
+        assert_eq!(
+            rules_of(raw, &config()),
+            vec![
+                ("commit_type".to_string(), Some(1)),
+                ("subject_length".to_string(), Some(1)),
+                ("body_wrap".to_string(), Some(2)),
+                ("body_wrap".to_string(), Some(4)),
+            ]
+        );
+        assert_eq!(
+            rules_of("chore: tidy up", &config()),
+            vec![("commit_type".to_string(), Some(1))]
+        );
+    }
+
+    #[test]
+    fn test_issue_key_and_verbose_diff() {
+        let config = CommitMsgConfig {
+            issue_key: Some("[A-Z]+-[0-9]+".into()),
+            ..config()
+        };
+        assert!(rules_of("fix: crash\n\nRefs GC-42\n", &config).is_empty());
+        let raw = format!("fix: crash\n{}\n+ GC-42 in the diff\n", SCISSORS);
+        assert_eq!(
+            rules_of(&raw, &config),
+            vec![("issue_key".to_string(), None)]
+        );
+    }
+}
@@ -1,11 +1,14 @@
-use syn::{File, Item};
-
-use syn::ItemFn;
+use syn::{Block, File, ImplItem, Item, Signature};
 
 #[allow(dead_code)] //max_nesting_depth, return_count, param_count not used yet.
 #[derive(Debug)]
 pub struct FunctionComplexity {
     pub name: String,
+    pub qualified_name: String,
+    /// Line of the `fn` keyword (1-based)
+    pub start_line: usize,
+    /// Line of the closing brace of the body (1-based)
+    pub end_line: usize,
     pub lines_of_code: usize,
     pub cyclomatic_complexity: usize,
     pub max_nesting_depth: usize,
@@ -47,8 +50,8 @@ impl<'ast> Visit<'ast> for ComplexityVisitor {
     }
 }
 
-pub fn analyze_function(func: &ItemFn) -> FunctionComplexity {
-    let loc = func.block.stmts.len(); // Rough LOC as number of statements
+fn analyze_signature_and_block(sig: &Signature, block: &Block, prefix: &str) -> FunctionComplexity {
+    let loc = block.stmts.len(); // Rough LOC as number of statements
 
     let mut visitor = ComplexityVisitor {
         cyclomatic_complexity: 1, // baseline
@@ -56,12 +59,16 @@ pub fn analyze_function(func: &ItemFn) -> FunctionComplexity {
         current_depth: 0,
         return_count: 0,
     };
-    visitor.visit_block(&func.block);
+    visitor.visit_block(block);
 
-    let param_count = func.sig.inputs.len();
+    let param_count = sig.inputs.len();
+    let name = sig.ident.to_string();
 
     FunctionComplexity {
-        name: func.sig.ident.to_string(),
+        qualified_name: qualify(prefix, &name),
+        name,
+        start_line: sig.fn_token.span.start().line,
+        end_line: block.brace_token.span.close().end().line,
         lines_of_code: loc,
         cyclomatic_complexity: visitor.cyclomatic_complexity,
         max_nesting_depth: visitor.max_depth,
@@ -70,15 +77,116 @@ pub fn analyze_function(func: &ItemFn) -> FunctionComplexity {
     }
 }
 
+/// Joins a module/impl path prefix and a name with `::`.
+fn qualify(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", prefix, name)
+    }
+}
+
+/// Collects metrics for free functions, inline modules and inherent/trait impl methods.
+///
+/// Methods are qualified with their self type (e.g. `parser::Lexer::next_token`) so that
+/// two functions with the same name in one file can be told apart.
+fn analyze_items(items: &[Item], prefix: &str, metrics: &mut Vec<FunctionComplexity>) {
+    for item in items {
+        match item {
+            Item::Fn(func) => {
+                metrics.push(analyze_signature_and_block(&func.sig, &func.block, prefix))
+            }
+            Item::Mod(module) => {
+                if let Some((_, content)) = &module.content {
+                    analyze_items(
+                        content,
+                        &qualify(prefix, &module.ident.to_string()),
+                        metrics,
+                    );
+                }
+            }
+            Item::Impl(item_impl) => {
+                let self_ty = type_name(&item_impl.self_ty);
+                let impl_prefix = qualify(prefix, &self_ty);
+                for impl_item in &item_impl.items {
+                    if let ImplItem::Fn(method) = impl_item {
+                        metrics.push(analyze_signature_and_block(
+                            &method.sig,
+                            &method.block,
+                            &impl_prefix,
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the last path segment of a type (`crate::foo::Bar<T>` -> `Bar`).
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        syn::Type::Reference(reference) => type_name(&reference.elem),
+        _ => "_".to_string(),
+    }
+}
+
 pub fn analyze_file(file: &File) -> Vec<FunctionComplexity> {
-    file.items
-        .iter()
-        .filter_map(|item| {
-            if let Item::Fn(func) = item {
-                Some(analyze_function(func))
-            } else {
-                None
+    let mut metrics = Vec::new();
+    analyze_items(&file.items, "", &mut metrics);
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::analyze_file;
+
+    #[test]
+    fn test_qualified_names() {
+        let code = r#"
+            fn free() {}
+            mod inner {
+                struct Lexer;
+                impl Lexer {
+                    fn next_token(&self) {}
+                }
             }
-        })
-        .collect()
+        "#;
+        let file = syn::parse_file(code).unwrap();
+        let names: Vec<String> = analyze_file(&file)
+            .into_iter()
+            .map(|m| m.qualified_name)
+            .collect();
+        assert_eq!(names, vec!["free", "inner::Lexer::next_token"]);
+    }
+
+    #[test]
+    fn test_function_lines() {
+        let code = "fn one() {}\n\nfn two() {\n    let _ = 1;\n}\n";
+        let file = syn::parse_file(code).unwrap();
+        let metrics = analyze_file(&file);
+        assert_eq!((metrics[0].start_line, metrics[0].end_line), (1, 1));
+        assert_eq!((metrics[1].start_line, metrics[1].end_line), (3, 5));
+    }
+
+    #[test]
+    fn test_cyclomatic_complexity() {
+        let code = r#"
+            fn branches(x: u8) -> u8 {
+                if x > 1 { return 1; }
+                match x { 0 => 0, _ => 2 }
+            }
+        "#;
+        let file = syn::parse_file(code).unwrap();
+        let metrics = analyze_file(&file);
+        assert_eq!(metrics[0].cyclomatic_complexity, 3);
+        assert_eq!(metrics[0].return_count, 1);
+        assert_eq!(metrics[0].param_count, 1);
+    }
 }
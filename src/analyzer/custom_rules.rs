@@ -1,21 +1,341 @@
 use std::fs;
 
 use serde::Deserialize;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{Attribute, ExprMethodCall, ExprPath, ImplItemFn, ItemFn, ItemMod, Macro, Token};
 
-#[derive(Debug, Deserialize)]
+use crate::analyzer::finding::{Finding, Severity, TextEdit};
+use crate::analyzer::messages::custom_rule;
+use crate::analyzer::script_rules::{self, SourceView};
+use crate::config::GrumpinessLevel;
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct RuleConfig {
     pub name: String,
     pub enabled: bool,
     pub threshold: Option<u32>,
     pub option: Option<String>,
+    /// Text offered as fix for the violation (e.g. `info!` for `forbid_word = "println!"`)
+    pub replacement: Option<String>,
+    /// Path to a Rhai script implementing the rule (relative to the rules file)
+    pub script: Option<String>,
+    /// Inline Rhai script implementing the rule
+    pub script_source: Option<String>,
+    /// Syntax shape the rule forbids
+    pub pattern: Option<AstPattern>,
+    /// `deny` makes violations block commits in the pre-commit hook
+    #[serde(default)]
+    pub severity: Severity,
+    /// Script compiled when the rules file was loaded
+    #[serde(skip)]
+    pub compiled_script: Option<rhai::AST>,
 }
 
-fn no_todo_comments(source: &str) -> bool {
-    source.to_lowercase().contains("todo")
+/// Declarative description of a forbidden syntax shape, e.g.
+///
+/// ```toml
+/// [[rules]]
+/// name = "no_unwrap"
+/// enabled = true
+/// pattern = { method_call = "unwrap", not_in_test = true }
+/// ```
+///
+/// Every match of any of the given shapes is reported, unless it is excluded by a scope
+/// filter. Matching is purely syntactic: `method_call = "unwrap"` also matches
+/// `Option::unwrap`, and `path = "std::process::exit"` matches `exit`, `process::exit` and
+/// `std::process::exit`, but not `other::exit`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AstPattern {
+    /// Name of a called method (`x.unwrap()`)
+    pub method_call: Option<String>,
+    /// Path of a referenced item (`std::process::exit`)
+    pub path: Option<String>,
+    /// Name of an invoked macro without `!` (`dbg`)
+    pub macro_name: Option<String>,
+    /// Name of an attribute (`allow`, `deprecated`)
+    pub attribute: Option<String>,
+    /// Skip matches inside `#[test]` functions and `#[cfg(test)]` modules
+    #[serde(default)]
+    pub not_in_test: bool,
+    /// Skip matches inside functions with one of these names (`["main"]`)
+    #[serde(default)]
+    pub not_in_fn: Vec<String>,
+    /// Message reported for a match
+    pub message: Option<String>,
+}
+
+impl AstPattern {
+    fn description(&self) -> String {
+        [
+            self.method_call.as_ref().map(|m| format!("`.{}()`", m)),
+            self.path.as_ref().map(|p| format!("`{}`", p)),
+            self.macro_name.as_ref().map(|m| format!("`{}!`", m)),
+            self.attribute.as_ref().map(|a| format!("`#[{}]`", a)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
 }
 
-fn contains_forbidden_word(source: &str, word: &str) -> bool {
-    source.contains(word)
+/// Walks a file and records the line of every match of an [`AstPattern`].
+struct PatternVisitor<'a> {
+    pattern: &'a AstPattern,
+    path_segments: Vec<String>,
+    test_depth: usize,
+    functions: Vec<String>,
+    matches: Vec<usize>,
+}
+
+impl<'a> PatternVisitor<'a> {
+    fn new(pattern: &'a AstPattern) -> Self {
+        PatternVisitor {
+            pattern,
+            path_segments: pattern
+                .path
+                .as_deref()
+                .map(|p| p.split("::").map(str::to_string).collect())
+                .unwrap_or_default(),
+            test_depth: 0,
+            functions: vec![],
+            matches: vec![],
+        }
+    }
+
+    fn in_excluded_scope(&self) -> bool {
+        (self.pattern.not_in_test && self.test_depth > 0)
+            || self
+                .functions
+                .iter()
+                .any(|name| self.pattern.not_in_fn.contains(name))
+    }
+
+    fn record(&mut self, span: proc_macro2::Span) {
+        if !self.in_excluded_scope() {
+            self.matches.push(span.start().line);
+        }
+    }
+
+    /// Runs `walk` inside the scope of a function or module carrying `attrs`.
+    fn scoped(&mut self, attrs: &[Attribute], name: Option<String>, walk: impl FnOnce(&mut Self)) {
+        let is_test = attrs.iter().any(is_test_attribute);
+        self.test_depth += is_test as usize;
+        if let Some(name) = &name {
+            self.functions.push(name.clone());
+        }
+        walk(self);
+        if name.is_some() {
+            self.functions.pop();
+        }
+        self.test_depth -= is_test as usize;
+    }
+}
+
+impl<'ast> Visit<'ast> for PatternVisitor<'_> {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let name = node.sig.ident.to_string();
+        self.scoped(&node.attrs, Some(name), |v| visit::visit_item_fn(v, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        let name = node.sig.ident.to_string();
+        self.scoped(&node.attrs, Some(name), |v| {
+            visit::visit_impl_item_fn(v, node)
+        });
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        self.scoped(&node.attrs, None, |v| visit::visit_item_mod(v, node));
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        if self
+            .pattern
+            .method_call
+            .as_ref()
+            .is_some_and(|name| node.method == name)
+        {
+            self.record(node.method.span());
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_path(&mut self, node: &'ast ExprPath) {
+        let written: Vec<String> = node
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        if !self.path_segments.is_empty() && self.path_segments.ends_with(&written) {
+            self.record(node.path.segments[0].ident.span());
+        }
+        visit::visit_expr_path(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        if let (Some(name), Some(last)) = (&self.pattern.macro_name, node.path.segments.last())
+            && last.ident == name
+        {
+            self.record(last.ident.span());
+        }
+        visit::visit_macro(self, node);
+    }
+
+    fn visit_attribute(&mut self, node: &'ast Attribute) {
+        if self
+            .pattern
+            .attribute
+            .as_ref()
+            .is_some_and(|name| node.path().segments.last().is_some_and(|s| s.ident == name))
+        {
+            self.record(node.pound_token.span);
+        }
+        visit::visit_attribute(self, node);
+    }
+}
+
+/// `#[test]`, `#[tokio::test]`, `#[cfg(test)]` and friends.
+fn is_test_attribute(attr: &Attribute) -> bool {
+    if attr
+        .path()
+        .segments
+        .last()
+        .is_some_and(|s| s.ident == "test")
+    {
+        return true;
+    }
+    match &attr.meta {
+        syn::Meta::List(list) if list.path.is_ident("cfg") => list
+            .parse_args::<syn::Meta>()
+            .is_ok_and(|predicate| cfg_without_test(&predicate) == Some(false)),
+        _ => false,
+    }
+}
+
+/// Value of a `cfg` predicate when not compiling tests, `None` if it depends on other
+/// options. Code whose predicate is `Some(false)` is only compiled for tests, e.g. below
+/// `#[cfg(test)]` or `#[cfg(all(test, unix))]`, but not below `#[cfg(not(test))]`.
+fn cfg_without_test(predicate: &syn::Meta) -> Option<bool> {
+    let syn::Meta::List(list) = predicate else {
+        return predicate.path().is_ident("test").then_some(false);
+    };
+    let operands = list
+        .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        .ok()?;
+    let mut values = operands.iter().map(cfg_without_test);
+    if list.path.is_ident("not") {
+        values.next()?.map(|value| !value)
+    } else if list.path.is_ident("all") {
+        let values: Vec<_> = values.collect();
+        match values.contains(&Some(false)) {
+            true => Some(false),
+            false => values.iter().all(Option::is_some).then_some(true),
+        }
+    } else if list.path.is_ident("any") {
+        let values: Vec<_> = values.collect();
+        match values.contains(&Some(true)) {
+            true => Some(true),
+            false => values.iter().all(Option::is_some).then_some(false),
+        }
+    } else {
+        None
+    }
+}
+
+/// Reports every match of `pattern` in `syntax` as a finding of `rule`.
+fn apply_pattern(
+    rule: &str,
+    pattern: &AstPattern,
+    syntax: &syn::File,
+    level: &GrumpinessLevel,
+) -> Result<Vec<Finding>, String> {
+    let description = pattern.description();
+    if description.is_empty() {
+        return Err(format!(
+            "Pattern of rule '{}' needs one of method_call, path, macro_name or attribute",
+            rule
+        ));
+    }
+
+    let mut visitor = PatternVisitor::new(pattern);
+    visitor.visit_file(syntax);
+
+    let message = pattern
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("Forbidden pattern found: {}", description));
+    Ok(visitor
+        .matches
+        .into_iter()
+        .map(|line| {
+            Finding::new(
+                rule,
+                None,
+                generate_message(rule, Some(message.clone()), level),
+            )
+            .at_line(line)
+        })
+        .collect())
+}
+
+impl RuleConfig {
+    fn is_scripted(&self) -> bool {
+        self.script.is_some() || self.script_source.is_some()
+    }
+}
+
+/// Lines (1-based) mentioning a TODO.
+fn todo_lines(source: &str) -> Vec<usize> {
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains("todo"))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// Line (1-based) containing the given byte offset.
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Occurrences of `word` that are not part of a longer identifier, so that `println!` does
+/// not match inside `eprintln!` or `my_println!`.
+fn forbidden_word_matches<'a>(
+    source: &'a str,
+    word: &'a str,
+) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+    source.match_indices(word).filter(move |(start, matched)| {
+        let end = start + matched.len();
+        let starts_token = !word.starts_with(is_identifier_char)
+            || !source[..*start].ends_with(is_identifier_char);
+        let ends_token =
+            !word.ends_with(is_identifier_char) || !source[end..].starts_with(is_identifier_char);
+        starts_token && ends_token
+    })
+}
+
+fn replace_forbidden_word(start: usize, matched: &str, replacement: &str) -> TextEdit {
+    TextEdit {
+        start,
+        end: start + matched.len(),
+        original: matched.to_string(),
+        replacement: replacement.to_string(),
+    }
+}
+
+/// Layout of a rules file: a list of `[[rules]]` tables.
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleConfig>,
 }
 
 pub fn load_custom_rules_from_toml(path: &str) -> Result<Option<Vec<RuleConfig>>, String> {
@@ -27,55 +347,352 @@ pub fn load_custom_rules_from_toml(path: &str) -> Result<Option<Vec<RuleConfig>>
         Ok(content) => content,
         Err(e) => return Err(format!("Failed to read ruleset file '{}': {}", path, e)),
     };
-    let parsed: toml::Value = toml::from_str(&toml_str).expect("Invalid TOML");
+    let mut parsed: RulesFile =
+        toml::from_str(&toml_str).map_err(|e| format!("Invalid ruleset file '{}': {}", path, e))?;
 
-    parsed["rules"]
-        .as_array()
-        .expect("Expected 'rules' array")
-        .iter()
-        .map(|rule| toml::from_str(&rule.to_string()).expect("Invalid rule format"))
-        .collect()
+    // Scripts are referenced relative to the rules file
+    let rules_dir = std::path::Path::new(path)
+        .parent()
+        .unwrap_or(std::path::Path::new(""));
+    for rule in &mut parsed.rules {
+        if let Some(script) = &rule.script {
+            rule.script = Some(rules_dir.join(script).to_string_lossy().into_owned());
+        }
+        if rule.is_scripted() {
+            rule.compiled_script = Some(script_rules::compile_script(rule)?);
+        }
+    }
+
+    Ok(Some(parsed.rules))
+}
+
+pub fn apply_rules(rules: Vec<RuleConfig>, source: &str) -> Result<(bool, Vec<Finding>), String> {
+    apply_rules_by(&rules, source, &|_| GrumpinessLevel::Mild)
 }
 
-pub fn apply_rules(rules: Vec<RuleConfig>, source: &str) -> Result<(bool, Vec<String>), String> {
+/// Like `apply_rules`, with the grumpiness of the messages chosen per rule by `level_of`.
+pub fn apply_rules_by(
+    rules: &[RuleConfig],
+    source: &str,
+    level_of: &dyn Fn(&str) -> GrumpinessLevel,
+) -> Result<(bool, Vec<Finding>), String> {
     let mut messages = vec![];
     let mut successful = true;
+    let mut source_view: Option<SourceView> = None;
+    let mut syntax: Option<syn::File> = None;
 
     for rule in rules {
         if !rule.enabled {
             continue;
         }
+        let level = level_of(&rule.name);
+        let mut findings = apply_rule(rule, source, &level, &mut source_view, &mut syntax)?;
+        // Several rules may share a name (e.g. `forbid_word`), so the severity comes from
+        // the rule that produced the finding
+        for finding in &mut findings {
+            finding.severity = rule.severity;
+        }
+        successful &= findings.is_empty();
+        messages.extend(findings);
+    }
+    Ok((successful, messages))
+}
 
-        match rule.name.as_str() {
-            "no_todo_comments" => {
-                if no_todo_comments(source) {
-                    successful = false;
-                    messages.push(generate_message(
-                        rule.name,
-                        Some(String::from("TODO comments found!")),
-                    ));
-                }
+/// Findings of a single enabled rule. The parsed views of `source` are created on first
+/// use and shared between rules.
+fn apply_rule(
+    rule: &RuleConfig,
+    source: &str,
+    level: &GrumpinessLevel,
+    source_view: &mut Option<SourceView>,
+    syntax: &mut Option<syn::File>,
+) -> Result<Vec<Finding>, String> {
+    if rule.is_scripted() {
+        if source_view.is_none() {
+            *source_view = Some(SourceView::new(source)?);
+        }
+        return script_rules::apply_script_rule(rule, source_view.as_ref().unwrap(), level);
+    }
+
+    if let Some(pattern) = &rule.pattern {
+        if syntax.is_none() {
+            *syntax = Some(syn::parse_file(source).map_err(|e| format!("Syntax error: {}", e))?);
+        }
+        return apply_pattern(&rule.name, pattern, syntax.as_ref().unwrap(), level);
+    }
+
+    let mut findings = vec![];
+    match rule.name.as_str() {
+        // One finding per occurrence, so each points at its own line
+        "no_todo_comments" => {
+            for line in todo_lines(source) {
+                findings.push(
+                    Finding::new(
+                        &rule.name,
+                        None,
+                        generate_message(
+                            &rule.name,
+                            Some(String::from("TODO comments found!")),
+                            level,
+                        ),
+                    )
+                    .at_line(line),
+                );
             }
-            "forbid_word" => {
-                if let Some(forbidden_word) = rule.option {
-                    if contains_forbidden_word(source, &forbidden_word) {
-                        successful = false;
-                        messages.push(generate_message(
-                            rule.name,
-                            format!("Use of forbidden word: {}", forbidden_word).into(),
-                        ));
-                    }
+        }
+        "forbid_word" => {
+            if let Some(forbidden_word) = &rule.option {
+                for (start, matched) in forbidden_word_matches(source, forbidden_word) {
+                    let edits = rule
+                        .replacement
+                        .as_deref()
+                        .map(|replacement| {
+                            vec![replace_forbidden_word(start, matched, replacement)]
+                        })
+                        .unwrap_or_default();
+                    findings.push(
+                        Finding::new(
+                            &rule.name,
+                            None,
+                            generate_message(
+                                &rule.name,
+                                format!("Use of forbidden word: {}", forbidden_word).into(),
+                                level,
+                            ),
+                        )
+                        .with_edits(edits)
+                        .at_line(line_of(source, start)),
+                    );
                 }
             }
-            _ => {
-                Err(format!("Unknown rule: {}", rule.name))?;
-            }
+        }
+        _ => {
+            Err(format!("Unknown rule: {}", rule.name))?;
         }
     }
+    Ok(findings)
+}
 
-    Ok((successful, messages))
+fn generate_message(rule: &str, message: Option<String>, level: &GrumpinessLevel) -> String {
+    custom_rule::violation(level, rule, &format!("{:?}", Some(message)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forbid_word(word: &str, replacement: Option<&str>) -> RuleConfig {
+        RuleConfig {
+            name: "forbid_word".into(),
+            enabled: true,
+            option: Some(word.into()),
+            replacement: replacement.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_load_rules_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.toml");
+        fs::write(
+            &path,
+            "[[rules]]\nname = \"forbid_word\"\nenabled = true\noption = \"println!\"\n",
+        )
+        .unwrap();
+
+        let rules = load_custom_rules_from_toml(path.to_str().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].option.as_deref(), Some("println!"));
+        assert_eq!(rules[0].severity, Severity::Warn);
+        assert!(
+            load_custom_rules_from_toml("rules_do_not_exist.toml")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    fn pattern_rule(pattern: AstPattern) -> RuleConfig {
+        RuleConfig {
+            name: "pattern".into(),
+            enabled: true,
+            pattern: Some(pattern),
+            ..Default::default()
+        }
+    }
+
+    const PATTERN_SOURCE: &str = r#"
+fn main() {
+    let value = parse().unwrap();
+    std::process::exit(dbg!(value));
 }
 
-fn generate_message(rule: String, message: Option<String>) -> String {
-    return format!("Rule violation: {}\nmessage {:?}", rule, Some(message));
+fn helper() {
+    process::exit(1);
+    other::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parses() {
+        parse().unwrap();
+    }
+}
+"#;
+
+    fn pattern_lines(pattern: AstPattern) -> Vec<Option<usize>> {
+        let (_, findings) = apply_rules(vec![pattern_rule(pattern)], PATTERN_SOURCE).unwrap();
+        findings.into_iter().map(|f| f.line).collect()
+    }
+
+    #[test]
+    fn test_pattern_method_call_not_in_test() {
+        let pattern = AstPattern {
+            method_call: Some("unwrap".into()),
+            ..Default::default()
+        };
+        assert_eq!(pattern_lines(pattern.clone()), vec![Some(3), Some(16)]);
+        let pattern = AstPattern {
+            not_in_test: true,
+            ..pattern
+        };
+        assert_eq!(pattern_lines(pattern), vec![Some(3)]);
+    }
+
+    #[test]
+    fn test_pattern_not_in_test_evaluates_cfg() {
+        let source = r#"
+#[cfg(not(test))]
+fn production() { parse().unwrap(); }
+
+#[cfg(all(test, unix))]
+fn unix_test() { parse().unwrap(); }
+
+#[cfg(any(test, debug_assertions))]
+fn debug() { parse().unwrap(); }
+
+#[cfg(any(test, feature = "testing"))]
+fn testing() { parse().unwrap(); }
+"#;
+        let rule = pattern_rule(AstPattern {
+            method_call: Some("unwrap".into()),
+            not_in_test: true,
+            ..Default::default()
+        });
+        let (_, findings) = apply_rules(vec![rule], source).unwrap();
+        let lines: Vec<_> = findings.into_iter().map(|f| f.line).collect();
+        assert_eq!(lines, vec![Some(3), Some(9), Some(12)]);
+    }
+
+    #[test]
+    fn test_pattern_path_not_in_fn() {
+        let pattern = AstPattern {
+            path: Some("std::process::exit".into()),
+            not_in_fn: vec!["main".into()],
+            ..Default::default()
+        };
+        assert_eq!(pattern_lines(pattern), vec![Some(8)]);
+    }
+
+    #[test]
+    fn test_pattern_macro_and_attribute() {
+        let pattern = AstPattern {
+            macro_name: Some("dbg".into()),
+            attribute: Some("test".into()),
+            ..Default::default()
+        };
+        assert_eq!(pattern_lines(pattern), vec![Some(4), Some(14)]);
+    }
+
+    #[test]
+    fn test_empty_pattern_is_rejected() {
+        assert!(apply_rules(vec![pattern_rule(AstPattern::default())], PATTERN_SOURCE).is_err());
+    }
+
+    #[test]
+    fn test_forbid_word_offers_replacement() {
+        let source = "fn main() { println!(\"a\"); println!(\"b\"); }";
+        let (successful, findings) =
+            apply_rules(vec![forbid_word("println!", Some("info!"))], source).unwrap();
+        assert!(!successful);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].rule, "forbid_word");
+        assert_eq!(findings[0].edits.len(), 1);
+        assert_eq!(findings[0].edits[0].start, 12);
+        assert_eq!(findings[1].edits[0].start, 27);
+    }
+
+    #[test]
+    fn test_every_occurrence_is_a_finding_on_its_line() {
+        let source = "// TODO: one\nfn main() {\n    println!(\"a\");\n}\n// todo: two\n";
+        let rules = vec![
+            forbid_word("println!", None),
+            RuleConfig {
+                name: "no_todo_comments".into(),
+                enabled: true,
+                ..Default::default()
+            },
+        ];
+        let (_, findings) = apply_rules(rules, source).unwrap();
+        let lines: Vec<_> = findings.iter().map(|f| (f.rule.as_str(), f.line)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                ("forbid_word", Some(3)),
+                ("no_todo_comments", Some(1)),
+                ("no_todo_comments", Some(5))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_forbid_word_ignores_longer_identifiers() {
+        let source = "fn main() { eprintln!(\"a\"); my_println!(\"b\"); println!(\"c\"); }";
+        let (_, findings) =
+            apply_rules(vec![forbid_word("println!", Some("info!"))], source).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            crate::analyzer::fix::apply_edits(source, &findings[0].edits).unwrap(),
+            "fn main() { eprintln!(\"a\"); my_println!(\"b\"); info!(\"c\"); }"
+        );
+
+        let (successful, findings) =
+            apply_rules(vec![forbid_word("unsafe", None)], "fn unsafe_fn() {}").unwrap();
+        assert!(successful);
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_forbid_word_without_replacement_is_not_fixable() {
+        let (_, findings) =
+            apply_rules(vec![forbid_word("unsafe", None)], "unsafe fn f() {}").unwrap();
+        assert!(!findings[0].is_fixable());
+    }
+
+    #[test]
+    fn test_deny_severity_is_attached_to_findings() {
+        let rule = RuleConfig {
+            severity: Severity::Deny,
+            ..forbid_word("unsafe", None)
+        };
+        let (_, findings) = apply_rules(vec![rule], "unsafe fn f() {}").unwrap();
+        assert_eq!(findings[0].severity, Severity::Deny);
+    }
+
+    #[test]
+    fn test_severity_comes_from_the_rule_instance() {
+        let rules = vec![
+            RuleConfig {
+                severity: Severity::Deny,
+                ..forbid_word("unsafe", None)
+            },
+            forbid_word("println!", None),
+        ];
+        let (_, findings) = apply_rules(rules, "unsafe fn f() { println!(); }").unwrap();
+        let severities: Vec<_> = findings.iter().map(|f| f.severity).collect();
+        assert_eq!(severities, vec![Severity::Deny, Severity::Warn]);
+    }
 }
@@ -0,0 +1,305 @@
+//! Why Clippy complains: rationale, bad and good code and refactoring tips per rule.
+//!
+//! Built-in rules are explained from `assets/explanations.toml`, custom rules from their
+//! definition in the rules file and clippy lints from the documentation shipped with
+//! clippy itself (`cargo clippy --explain`), so explanations work offline.
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process::Command;
+
+use crate::analyzer::custom_rules::{RuleConfig, load_custom_rules_from_toml};
+use crate::analyzer::finding::Severity;
+
+const BUILTIN_EXPLANATIONS: &str = include_str!("../../assets/explanations.toml");
+
+/// Online index of the clippy lints, anchored by lint name
+const CLIPPY_LINTS_URL: &str = "https://rust-lang.github.io/rust-clippy/master/index.html";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Explanation {
+    #[serde(skip)]
+    pub rule: String,
+    pub rationale: String,
+    /// Code breaking the rule
+    pub bad: Option<String>,
+    /// The same code following the rule
+    pub good: Option<String>,
+    #[serde(default)]
+    pub tips: Vec<String>,
+    /// Link to further documentation
+    pub docs: Option<String>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "❓ {}\n", self.rule)?;
+        writeln!(f, "{}", self.rationale.trim())?;
+        if let Some(bad) = &self.bad {
+            writeln!(f, "\n👎 Bad:\n{}", indent(bad))?;
+        }
+        if let Some(good) = &self.good {
+            writeln!(f, "\n👍 Good:\n{}", indent(good))?;
+        }
+        if !self.tips.is_empty() {
+            writeln!(f, "\n💡 Tips:")?;
+            for tip in &self.tips {
+                writeln!(f, "  - {}", tip)?;
+            }
+        }
+        if let Some(docs) = &self.docs {
+            writeln!(f, "\n📖 {}", docs)?;
+        }
+        Ok(())
+    }
+}
+
+fn indent(code: &str) -> String {
+    code.trim_matches('\n')
+        .lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Explanations of the built-in rules by rule id.
+pub fn builtin_explanations() -> BTreeMap<String, Explanation> {
+    let mut explanations: BTreeMap<String, Explanation> =
+        toml::from_str(BUILTIN_EXPLANATIONS).expect("built-in explanations are valid TOML");
+    for (rule, explanation) in &mut explanations {
+        explanation.rule = rule.clone();
+    }
+    explanations
+}
+
+/// Explains a rule: a built-in rule, a custom rule from `custom_rules` or a clippy lint
+/// (`clippy::needless_return`, `needless-return` or just `needless_return`).
+pub fn explain(rule_id: &str, custom_rules: &str) -> Result<Explanation, String> {
+    let lint = rule_id.strip_prefix("clippy::");
+    if lint.is_none() {
+        if let Some(explanation) = builtin_explanations().remove(rule_id) {
+            return Ok(explanation);
+        }
+        let rules = load_custom_rules_from_toml(custom_rules)?.unwrap_or_default();
+        if let Some(rule) = rules.iter().find(|rule| rule.name == rule_id) {
+            return Ok(explain_custom_rule(rule));
+        }
+    }
+
+    let lint = lint.unwrap_or(rule_id).replace('-', "_").to_lowercase();
+    match clippy_lint_docs(&lint) {
+        Some(docs) => Ok(explain_clippy_lint(&lint, &docs)),
+        None => Err(format!(
+            "Unknown rule '{}'. Built-in rules: {}; custom rules are looked up in '{}' and clippy lints with `cargo clippy --explain`",
+            rule_id,
+            builtin_explanations()
+                .into_keys()
+                .collect::<Vec<_>>()
+                .join(", "),
+            custom_rules
+        )),
+    }
+}
+
+/// Explains a custom rule from what its definition says about it.
+fn explain_custom_rule(rule: &RuleConfig) -> Explanation {
+    let mut rationale = format!("'{}' is a custom rule of this project", rule.name);
+    if let Some(pattern) = &rule.pattern {
+        let shapes: Vec<String> = [
+            pattern
+                .method_call
+                .as_ref()
+                .map(|m| format!("calls of `.{}()`", m)),
+            pattern.path.as_ref().map(|p| format!("uses of `{}`", p)),
+            pattern
+                .macro_name
+                .as_ref()
+                .map(|m| format!("`{}!` invocations", m)),
+            pattern
+                .attribute
+                .as_ref()
+                .map(|a| format!("`#[{}]` attributes", a)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        rationale += &format!(" forbidding {}", shapes.join(" and "));
+        if pattern.not_in_test {
+            rationale += " outside of tests";
+        }
+        if !pattern.not_in_fn.is_empty() {
+            rationale += &format!(" (allowed in {})", pattern.not_in_fn.join(", "));
+        }
+        if let Some(message) = &pattern.message {
+            rationale += &format!(": {}", message);
+        }
+    } else if let Some(script) = &rule.script {
+        rationale += &format!(" implemented by the script '{}'", script);
+    } else if rule.script_source.is_some() {
+        rationale += " implemented by an inline script";
+    }
+    rationale += ".";
+
+    let mut tips = vec![];
+    if let Some(replacement) = &rule.replacement {
+        tips.push(format!("Use `{}` instead.", replacement));
+    }
+    if rule.severity == Severity::Deny {
+        tips.push("Violations block commits in the pre-commit hook.".to_string());
+    }
+    tips.push("Ask the team about the reasons behind the rule.".to_string());
+
+    Explanation {
+        rule: rule.name.clone(),
+        rationale,
+        tips,
+        ..Default::default()
+    }
+}
+
+/// Documentation of a clippy lint as shipped with the installed clippy.
+fn clippy_lint_docs(lint: &str) -> Option<String> {
+    let output = Command::new("cargo")
+        .args(["clippy", "--explain", lint])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Builds an explanation from the markdown documentation of a clippy lint: what it does and
+/// why as rationale, the first two code blocks of its example as bad and good code.
+fn explain_clippy_lint(lint: &str, docs: &str) -> Explanation {
+    let mut rationale = vec![];
+    let mut examples = vec![];
+    let mut tips = vec![];
+    let mut heading = "";
+    let mut code: Option<Vec<&str>> = None;
+
+    for line in docs.lines() {
+        if line.starts_with("```") {
+            match code.take() {
+                Some(block) => examples.push(block.join("\n")),
+                None => code = Some(vec![]),
+            }
+        } else if let Some(block) = &mut code {
+            // Lines rustdoc hides from the rendered example
+            if line != "#" && !line.starts_with("# ") {
+                block.push(line);
+            }
+        } else if let Some(title) = line.strip_prefix("### ") {
+            heading = title;
+        } else if heading == "What it does" || heading.starts_with("Why") {
+            rationale.push(line);
+        } else if heading == "Known problems" && !line.trim().is_empty() {
+            tips.push(line.trim());
+        }
+    }
+
+    let mut tips = match tips.is_empty() {
+        true => vec![],
+        false => vec![format!("Known problems: {}", tips.join(" "))],
+    };
+    tips.push(format!(
+        "If the lint is wrong here, allow it locally with `#[allow(clippy::{})]` and say why.",
+        lint
+    ));
+    let mut examples = examples.into_iter();
+    Explanation {
+        rule: format!("clippy::{}", lint),
+        rationale: rationale.join("\n").trim().to_string(),
+        bad: examples.next(),
+        good: examples.next(),
+        tips,
+        docs: Some(format!("{}#{}", CLIPPY_LINTS_URL, lint)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::finding::rules;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builtin_rules_are_explained() {
+        let explanations = builtin_explanations();
+        for rule in [
+            rules::COMPLEXITY,
+            rules::FUNCTION_SIZE,
+            rules::COMMIT_TYPE,
+            rules::SUBJECT_LENGTH,
+            rules::BODY_WRAP,
+            rules::ISSUE_KEY,
+            "no_todo_comments",
+            "forbid_word",
+            "git_is_stale",
+            "git_most_frequent_author",
+            "codeowners",
+        ] {
+            let explanation = &explanations[rule];
+            assert_eq!(explanation.rule, rule);
+            assert!(!explanation.rationale.is_empty(), "{}", rule);
+            assert!(
+                explanation.bad.is_some() && explanation.good.is_some(),
+                "{}",
+                rule
+            );
+            assert!(!explanation.tips.is_empty(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn test_explain_custom_rule() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rules.toml");
+        fs::write(
+            &path,
+            "[[rules]]\nname = \"no_unwrap\"\nenabled = true\nseverity = \"deny\"\n\
+             pattern = { method_call = \"unwrap\", not_in_test = true, message = \"Handle the error\" }\n",
+        )
+        .unwrap();
+
+        let explanation = explain("no_unwrap", path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            explanation.rationale,
+            "'no_unwrap' is a custom rule of this project forbidding calls of `.unwrap()` outside of tests: Handle the error."
+        );
+        assert!(
+            explanation
+                .tips
+                .iter()
+                .any(|tip| tip.contains("pre-commit"))
+        );
+    }
+
+    #[test]
+    fn test_explain_clippy_lint_docs() {
+        let docs = "### What it does\nChecks for return statements at the end of a block.\n\n\
+                    ### Why is this bad?\nRemoving the `return` is more rusty.\n\n\
+                    ### Example\n```rust\n# fn main() {}\nfn foo(x: usize) -> usize {\n    return x;\n}\n```\n\
+                    simplify to\n```rust\nfn foo(x: usize) -> usize {\n    x\n}\n```\n";
+
+        let explanation = explain_clippy_lint("needless_return", docs);
+        assert_eq!(explanation.rule, "clippy::needless_return");
+        assert_eq!(
+            explanation.rationale,
+            "Checks for return statements at the end of a block.\n\nRemoving the `return` is more rusty."
+        );
+        assert_eq!(
+            explanation.bad.as_deref(),
+            Some("fn foo(x: usize) -> usize {\n    return x;\n}")
+        );
+        assert_eq!(
+            explanation.good.as_deref(),
+            Some("fn foo(x: usize) -> usize {\n    x\n}")
+        );
+        assert_eq!(
+            explanation.docs.as_deref(),
+            Some("https://rust-lang.github.io/rust-clippy/master/index.html#needless_return")
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use serde::Deserialize;
+
+use crate::analyzer::git::{Attribution, DiffHunk};
+
+/// How serious a finding is. `deny` findings block commits in the pre-commit hook.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Warn,
+    Deny,
+}
+
+/// A single complaint raised by one of the analyzers for a file.
+///
+/// Findings are identified by the rule that produced them and, when applicable, the
+/// qualified name of the offending function. Line numbers are deliberately left out so
+/// that a finding keeps its identity while the surrounding code moves around.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule: String,
+    pub function: Option<String>,
+    pub message: String,
+    /// Line the finding points at (1-based), if it can be located
+    pub line: Option<usize>,
+    /// Last line covered by the finding (e.g. the end of a function), if it spans several
+    pub end_line: Option<usize>,
+    pub severity: Severity,
+    /// Who last touched the lines of the finding, if blamed
+    pub last_touched: Option<Attribution>,
+    /// Owners of the file according to `CODEOWNERS`
+    pub owners: Vec<String>,
+    /// Text edits that fix the finding; empty if the rule offers no fix.
+    pub edits: Vec<TextEdit>,
+}
+
+impl Finding {
+    pub fn new(rule: &str, function: Option<&str>, message: String) -> Self {
+        Finding {
+            rule: rule.to_string(),
+            function: function.map(str::to_string),
+            message,
+            line: None,
+            end_line: None,
+            severity: Severity::Warn,
+            last_touched: None,
+            owners: vec![],
+            edits: vec![],
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Marks the finding as covering the lines from its `line` up to `end_line`.
+    pub fn until_line(mut self, end_line: usize) -> Self {
+        self.end_line = Some(end_line);
+        self
+    }
+
+    /// Whether the finding touches one of the given hunks. Findings that cannot be located
+    /// in the file always do.
+    pub fn overlaps_any(&self, hunks: &[DiffHunk]) -> bool {
+        match self.line {
+            Some(line) => {
+                let end_line = self.end_line.unwrap_or(line);
+                hunks.iter().any(|hunk| hunk.overlaps(line, end_line))
+            }
+            None => true,
+        }
+    }
+
+    pub fn with_edits(mut self, edits: Vec<TextEdit>) -> Self {
+        self.edits = edits;
+        self
+    }
+
+    pub fn is_fixable(&self) -> bool {
+        !self.edits.is_empty()
+    }
+}
+
+/// Replacement of the byte range `start..end` of a source file.
+///
+/// `original` holds the text the range covered when the edit was computed, so that an
+/// edit is not applied to a file that has changed in the meantime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub original: String,
+    pub replacement: String,
+}
+
+/// Rule ids of the built-in (non custom) checks.
+pub mod rules {
+    pub const CLIPPY: &str = "clippy";
+    pub const GIT_IS_STALE: &str = "git_is_stale";
+    pub const CODEOWNERS: &str = "codeowners";
+    pub const COMPLEXITY: &str = "complexity";
+    pub const FUNCTION_SIZE: &str = "function_size";
+    pub const COMMIT_TYPE: &str = "commit_type";
+    pub const SUBJECT_LENGTH: &str = "subject_length";
+    pub const BODY_WRAP: &str = "body_wrap";
+    pub const ISSUE_KEY: &str = "issue_key";
+}
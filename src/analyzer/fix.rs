@@ -0,0 +1,137 @@
+//! Applies the text edits offered by findings to source files.
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::finding::TextEdit;
+
+/// Applies all `edits` to `source` and returns the fixed source.
+///
+/// Edits are applied all-or-nothing: if any two edits overlap, an edit is out of bounds,
+/// or the text under an edit no longer matches what it was computed for, nothing is
+/// changed and an error is returned.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> Result<String, FixError> {
+    let mut sorted: Vec<&TextEdit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| (edit.start, edit.end));
+
+    for pair in sorted.windows(2) {
+        if pair[1].start < pair[0].end {
+            return Err(FixError::Overlapping(pair[0].start, pair[1].start));
+        }
+    }
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in sorted {
+        match source.get(edit.start..edit.end) {
+            Some(text) if text == edit.original => {}
+            Some(_) => return Err(FixError::Outdated(edit.start)),
+            None => return Err(FixError::OutOfBounds(edit.start, edit.end)),
+        }
+        fixed.push_str(&source[cursor..edit.start]);
+        fixed.push_str(&edit.replacement);
+        cursor = edit.end;
+    }
+    fixed.push_str(&source[cursor..]);
+
+    Ok(fixed)
+}
+
+/// Applies `edits` to the file at `path` and returns the number of applied edits.
+pub fn fix_file(path: &Path, edits: &[TextEdit]) -> Result<usize, FixError> {
+    let source =
+        fs::read_to_string(path).map_err(|e| FixError::Io(path.to_path_buf(), e.to_string()))?;
+    let fixed = apply_edits(&source, edits)?;
+    fs::write(path, fixed).map_err(|e| FixError::Io(path.to_path_buf(), e.to_string()))?;
+    Ok(edits.len())
+}
+
+#[derive(Debug)]
+pub enum FixError {
+    Overlapping(usize, usize),
+    OutOfBounds(usize, usize),
+    Outdated(usize),
+    Io(PathBuf, String),
+}
+
+impl fmt::Display for FixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixError::Overlapping(first, second) => {
+                write!(
+                    f,
+                    "Edits at byte {} and {} overlap, refusing to fix",
+                    first, second
+                )
+            }
+            FixError::OutOfBounds(start, end) => {
+                write!(f, "Edit {}..{} is outside of the file", start, end)
+            }
+            FixError::Outdated(start) => {
+                write!(
+                    f,
+                    "File changed at byte {} since it was analyzed, refusing to fix",
+                    start
+                )
+            }
+            FixError::Io(filepath, error) => {
+                write!(
+                    f,
+                    "File '{}' could not be fixed. Error: {}",
+                    filepath.display(),
+                    error
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: usize, end: usize, original: &str, replacement: &str) -> TextEdit {
+        TextEdit {
+            start,
+            end,
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_edits_in_any_order() {
+        let source = "println!(\"a\"); println!(\"b\");";
+        let edits = vec![
+            edit(15, 23, "println!", "info!"),
+            edit(0, 8, "println!", "info!"),
+        ];
+        assert_eq!(
+            apply_edits(source, &edits).unwrap(),
+            "info!(\"a\"); info!(\"b\");"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_edits_are_refused() {
+        let source = "println!(\"a\");";
+        let edits = vec![edit(0, 8, "println!", "info!"), edit(0, 5, "print", "log")];
+        assert!(matches!(
+            apply_edits(source, &edits),
+            Err(FixError::Overlapping(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_outdated_and_out_of_bounds_edits_are_refused() {
+        let source = "eprintln!(\"a\");";
+        assert!(matches!(
+            apply_edits(source, &[edit(0, 8, "println!", "info!")]),
+            Err(FixError::Outdated(0))
+        ));
+        assert!(matches!(
+            apply_edits(source, &[edit(10, 80, "", "")]),
+            Err(FixError::OutOfBounds(10, 80))
+        ));
+    }
+}
@@ -1,36 +1,241 @@
 // src/git_inspector.rs
-use git2::{BlameOptions, Commit, Oid, Repository, StatusOptions, Time};
+use git2::{
+    BlameOptions, Commit, Delta, DiffOptions, ErrorCode, Mailmap, Oid, Patch, Repository,
+    Signature, Sort, Status, StatusOptions, Tree, TreeWalkMode, TreeWalkResult,
+};
 
-fn extract_path_from_src(path: &Path) -> Option<String> {
-    let delimiter = "src/".to_string();
-    path.to_str()
-        .unwrap_or("")
-        .split_once(&delimiter)
-        .map(|(_, rest)| format!("src/{}", rest)) // return owned String
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Absolute, symlink-free form of `path`. Falls back to the canonical parent for files
+/// that do not exist (anymore), e.g. right after a deletion.
+fn absolute_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    let absolute = std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or(absolute),
+        _ => absolute,
+    }
 }
 
-fn extract_repository_path(path: &Path) -> Option<String> {
-    let delimiter = "/src".to_string();
-    path.to_str()
-        .unwrap_or("")
-        .split_once(&delimiter)
-        .map(|(repo, _)| format!("{}", repo))
+/// What the working tree is compared against when computing changed lines.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffBase {
+    /// Unstaged changes only
+    Index,
+    /// Staged and unstaged changes
+    Head,
+    /// All changes since the working tree branched off the given branch (merge base)
+    Branch(String),
 }
 
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+impl From<&str> for DiffBase {
+    fn from(value: &str) -> Self {
+        match value {
+            "index" => DiffBase::Index,
+            _ if value.eq_ignore_ascii_case("head") => DiffBase::Head,
+            branch => DiffBase::Branch(branch.to_string()),
+        }
+    }
+}
+
+/// Range of changed lines (1-based, inclusive) in the working tree version of a file.
+///
+/// Pure deletions are represented by the lines surrounding the removed code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffHunk {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl DiffHunk {
+    fn new(new_start: u32, new_lines: u32) -> Self {
+        let start = new_start as usize;
+        match new_lines {
+            0 => DiffHunk {
+                start: start.max(1),
+                end: start + 1,
+            },
+            lines => DiffHunk {
+                start,
+                end: start + lines as usize - 1,
+            },
+        }
+    }
+
+    /// Whether the lines `start..=end` touch this hunk.
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        start <= self.end && self.start <= end
+    }
+}
+
+/// Who last touched a line and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    pub name: String,
+    pub email: String,
+    /// Seconds since the Unix epoch
+    pub time: i64,
+}
+
+impl Attribution {
+    fn from_signature(signature: &Signature) -> Self {
+        Attribution {
+            name: signature.name().unwrap_or("Unknown").to_string(),
+            email: signature.email().unwrap_or("").to_string(),
+            time: signature.when().seconds(),
+        }
+    }
+
+    /// Whether the line was touched by `user` (same email or, lacking one, same name).
+    pub fn is_by(&self, user: &Attribution) -> bool {
+        if !self.email.is_empty() && !user.email.is_empty() {
+            self.email.eq_ignore_ascii_case(&user.email)
+        } else {
+            self.name == user.name
+        }
+    }
+
+    /// Human readable age, e.g. `3 days ago`.
+    pub fn age(&self) -> String {
+        let days = (now() - self.time).max(0) / 86400;
+        match days {
+            0 => "today".to_string(),
+            1 => "yesterday".to_string(),
+            2..=59 => format!("{} days ago", days),
+            60..=729 => format!("{} months ago", days / 30),
+            _ => format!("{} years ago", days / 365),
+        }
+    }
+}
+
+/// Line by line attribution of the working tree version of a file.
+#[derive(Debug, Clone)]
+pub struct FileBlame {
+    lines: Vec<Attribution>,
+}
+
+impl FileBlame {
+    /// The most recent change among the lines `start..=end` (1-based); the first of them on
+    /// ties.
+    pub fn last_touched(&self, start: usize, end: usize) -> Option<&Attribution> {
+        let end = end.min(self.lines.len());
+        self.lines
+            .get(start.max(1) - 1..end)?
+            .iter()
+            .rev()
+            .max_by_key(|attribution| attribution.time)
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// A commit author, with aliases merged according to `.mailmap`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Author {
+    pub name: String,
+    pub email: String,
+}
+
+impl fmt::Display for Author {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+fn resolve_author(mailmap: &Mailmap, commit: &Commit) -> Result<Author, git2::Error> {
+    let signature = mailmap.resolve_signature(&commit.author())?;
+    Ok(Author {
+        name: signature.name().unwrap_or("Unknown").to_string(),
+        email: signature.email().unwrap_or("").to_string(),
+    })
+}
+
+/// A file changed by a commit, with the lines it changed in the committed version.
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub commit: Oid,
+    /// Path relative to the working directory
+    pub path: PathBuf,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// A hunk of the committed blame of a file, reduced to what is reported about it.
+#[derive(Debug, Clone, PartialEq)]
+struct BlamedHunk {
+    commit: Oid,
+    lines: usize,
+}
+
+/// Committed blames computed so far, valid for one HEAD and one state of the index.
+#[derive(Default)]
+struct BlameCache {
+    head: Option<Oid>,
+    index_modified: Option<SystemTime>,
+    blames: HashMap<(PathBuf, Oid), Rc<Vec<BlamedHunk>>>,
+}
 
 pub struct GitInspector {
     pub(crate) repo: Repository,
+    cache: RefCell<BlameCache>,
 }
 
 impl GitInspector {
-    pub fn new<P: AsRef<Path>>(repo_path: P) -> Result<Self, git2::Error> {
-        let repo_path_str = extract_repository_path(&repo_path.as_ref())
-            .ok_or_else(|| git2::Error::from_str("Invalid repository path"))?;
-        let repo: Repository = Repository::discover(Path::new(&repo_path_str))?;
-        Ok(GitInspector { repo })
+    /// Opens the repository containing `path` (a file or directory).
+    ///
+    /// The repository is discovered upwards from the file's directory, so nested checkouts,
+    /// files outside of `src/`, worktrees and submodules resolve to the innermost repository.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, git2::Error> {
+        let path = absolute_path(path.as_ref());
+        let start = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+        let repo: Repository = Repository::discover(start)?;
+        Ok(GitInspector {
+            repo,
+            cache: RefCell::new(BlameCache::default()),
+        })
+    }
+
+    /// Absolute working directory of the repository; bare repositories have none.
+    pub fn workdir(&self) -> Result<PathBuf, git2::Error> {
+        self.repo
+            .workdir()
+            .map(absolute_path)
+            .ok_or_else(|| git2::Error::from_str("Repository has no working directory"))
+    }
+
+    /// Returns `path` relative to the working directory of the repository.
+    pub fn relative_path<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, git2::Error> {
+        let workdir = self.workdir()?;
+        let path = absolute_path(path.as_ref());
+        path.strip_prefix(&workdir)
+            .map(Path::to_path_buf)
+            .map_err(|_| {
+                git2::Error::from_str(&format!(
+                    "'{}' is not inside repository '{}'",
+                    path.display(),
+                    workdir.display()
+                ))
+            })
     }
 
     pub fn list_changed_files(&self) -> Result<Vec<PathBuf>, git2::Error> {
@@ -46,9 +251,334 @@ impl GitInspector {
         Ok(changed_files)
     }
 
+    /// Files with staged changes (added, modified, renamed), relative to the working directory.
+    pub fn list_staged_files(&self) -> Result<Vec<PathBuf>, git2::Error> {
+        let staged = Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_RENAMED
+            | Status::INDEX_TYPECHANGE;
+        let statuses = self.repo.statuses(Some(&mut StatusOptions::new()))?;
+        let staged_files = statuses
+            .iter()
+            .filter(|entry| entry.status().intersects(staged))
+            .filter_map(|entry| {
+                entry
+                    .head_to_index()
+                    .and_then(|delta| delta.new_file().path().map(PathBuf::from))
+            })
+            .collect();
+
+        Ok(staged_files)
+    }
+
+    /// Content of `relative_path` as staged in the index, i.e. what is about to be committed.
+    pub fn staged_source(&self, relative_path: &Path) -> Result<String, git2::Error> {
+        let index = self.repo.index()?;
+        let entry = index.get_path(relative_path, 0).ok_or_else(|| {
+            git2::Error::from_str(&format!("'{}' is not staged", relative_path.display()))
+        })?;
+        let blob = self.repo.find_blob(entry.id)?;
+        String::from_utf8(blob.content().to_vec()).map_err(|_| {
+            git2::Error::from_str(&format!("'{}' is not valid UTF-8", relative_path.display()))
+        })
+    }
+
+    /// The `.git` directory of the repository (of the worktree, for linked worktrees).
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    /// Directory git runs hooks from: `core.hooksPath` if configured, otherwise the `hooks`
+    /// directory shared by all worktrees.
+    pub fn hooks_dir(&self) -> PathBuf {
+        let hooks_path = self
+            .repo
+            .config()
+            .and_then(|config| config.get_path("core.hooksPath"));
+        match (hooks_path, self.repo.workdir()) {
+            (Ok(path), Some(workdir)) => workdir.join(path),
+            (Ok(path), None) => path,
+            (Err(_), _) => self.repo.commondir().join("hooks"),
+        }
+    }
+
+    /// Changed line ranges of `path` in the working tree compared to `base`.
+    ///
+    /// Untracked files are reported as changed entirely.
+    pub fn diff_hunks<P: AsRef<Path>>(
+        &self,
+        path: P,
+        base: &DiffBase,
+    ) -> Result<Vec<DiffHunk>, git2::Error> {
+        let relative_path = self.relative_path(path)?;
+        let mut opts = DiffOptions::new();
+        opts.pathspec(relative_path.as_path())
+            .disable_pathspec_match(true)
+            .context_lines(0)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+
+        let diff = match base {
+            DiffBase::Index => self.repo.diff_index_to_workdir(None, Some(&mut opts))?,
+            DiffBase::Head => {
+                // An unborn HEAD (no commits yet) means everything is new
+                let tree = self
+                    .repo
+                    .head()
+                    .ok()
+                    .and_then(|head| head.peel_to_tree().ok());
+                self.repo
+                    .diff_tree_to_workdir_with_index(tree.as_ref(), Some(&mut opts))?
+            }
+            DiffBase::Branch(branch) => {
+                let tree = self.merge_base_tree(branch)?;
+                self.repo
+                    .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut opts))?
+            }
+        };
+
+        let mut hunks = Vec::new();
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |_, hunk| {
+                hunks.push(DiffHunk::new(hunk.new_start(), hunk.new_lines()));
+                true
+            }),
+            None,
+        )?;
+        Ok(hunks)
+    }
+
+    fn merge_base_tree(&self, branch: &str) -> Result<Tree<'_>, git2::Error> {
+        let branch = self.repo.revparse_single(branch)?.peel_to_commit()?;
+        let head = self.repo.head()?.peel_to_commit()?;
+        let merge_base = self.repo.merge_base(branch.id(), head.id())?;
+        self.repo.find_commit(merge_base)?.tree()
+    }
+
+    /// Files added or modified by the commits of the current branch during the last `days`
+    /// days, newest first. Merge commits count with their changes against the first parent.
+    pub fn recent_changes(&self, days: u64) -> Result<Vec<FileChange>, git2::Error> {
+        let period = i64::try_from(days.saturating_mul(86400)).unwrap_or(i64::MAX);
+        let cutoff = now().saturating_sub(period);
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        // History before the cutoff is not walked at all: the commits where it starts are
+        // hidden, and with them everything they lead to
+        let mut pending = vec![self.repo.head()?.peel_to_commit()?];
+        let mut seen = HashSet::new();
+        while let Some(commit) = pending.pop() {
+            if !seen.insert(commit.id()) {
+                continue;
+            }
+            if commit.time().seconds() < cutoff {
+                revwalk.hide(commit.id())?;
+            } else {
+                pending.extend(commit.parents());
+            }
+        }
+
+        let mut changes = Vec::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree()?),
+            };
+            let mut opts = DiffOptions::new();
+            opts.context_lines(0);
+            let diff = self.repo.diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&commit.tree()?),
+                Some(&mut opts),
+            )?;
+
+            for index in 0..diff.deltas().len() {
+                let Some(patch) = Patch::from_diff(&diff, index)? else {
+                    continue;
+                };
+                let delta = patch.delta();
+                let (Delta::Added | Delta::Modified | Delta::Renamed, Some(path)) =
+                    (delta.status(), delta.new_file().path())
+                else {
+                    continue;
+                };
+                let hunks = (0..patch.num_hunks())
+                    .map(|hunk| {
+                        patch
+                            .hunk(hunk)
+                            .map(|(hunk, _)| DiffHunk::new(hunk.new_start(), hunk.new_lines()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                changes.push(FileChange {
+                    commit: commit.id(),
+                    path: path.to_path_buf(),
+                    hunks,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Content of `relative_path` as committed in `commit`.
+    pub fn source_at(&self, commit: Oid, relative_path: &Path) -> Result<String, git2::Error> {
+        let tree = self.repo.find_commit(commit)?.tree()?;
+        let blob = tree
+            .get_path(relative_path)?
+            .to_object(&self.repo)?
+            .peel_to_blob()?;
+        String::from_utf8(blob.content().to_vec()).map_err(|_| {
+            git2::Error::from_str(&format!("'{}' is not valid UTF-8", relative_path.display()))
+        })
+    }
+
+    /// Whether `path` is modified, staged or untracked. Only the status of this one file is
+    /// computed, not the one of the whole working tree.
     pub fn is_file_changed<P: AsRef<Path>>(&self, path: P) -> Result<bool, git2::Error> {
-        let changed_files = self.list_changed_files()?;
-        Ok(changed_files.contains(&path.as_ref().to_path_buf()))
+        let relative_path = self.relative_path(path)?;
+        match self.repo.status_file(&relative_path) {
+            Ok(status) => Ok(!status.is_empty() && !status.contains(Status::IGNORED)),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Forgets all cached blames. They are also dropped automatically as soon as HEAD
+    /// moves or the index is written.
+    pub fn invalidate_cache(&self) {
+        *self.cache.borrow_mut() = BlameCache::default();
+    }
+
+    /// Blame of the committed version of `relative_path`, cached by the path and its blob
+    /// in HEAD so repeated saves of a file do not blame its whole history again.
+    fn committed_blame(&self, relative_path: &Path) -> Result<Rc<Vec<BlamedHunk>>, git2::Error> {
+        let head = self.repo.head()?.peel_to_commit()?;
+        let blob = head.tree()?.get_path(relative_path)?.id();
+        let index_modified = std::fs::metadata(self.repo.path().join("index"))
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let key = (relative_path.to_path_buf(), blob);
+        {
+            let mut cache = self.cache.borrow_mut();
+            if cache.head != Some(head.id()) || cache.index_modified != index_modified {
+                *cache = BlameCache {
+                    head: Some(head.id()),
+                    index_modified,
+                    blames: HashMap::new(),
+                };
+            }
+            if let Some(hunks) = cache.blames.get(&key) {
+                return Ok(Rc::clone(hunks));
+            }
+        }
+
+        let blame = self
+            .repo
+            .blame_file(relative_path, Some(&mut BlameOptions::new()))?;
+        let hunks = Rc::new(
+            blame
+                .iter()
+                .map(|hunk| BlamedHunk {
+                    commit: hunk.final_commit_id(),
+                    lines: hunk.lines_in_hunk(),
+                })
+                .collect::<Vec<_>>(),
+        );
+        self.cache
+            .borrow_mut()
+            .blames
+            .insert(key, Rc::clone(&hunks));
+        Ok(hunks)
+    }
+
+    /// The configured git user (`user.name` / `user.email`), timestamped now.
+    pub fn current_user(&self) -> Result<Attribution, git2::Error> {
+        let signature = self.repo.signature()?;
+        Ok(Attribution {
+            time: now(),
+            ..Attribution::from_signature(&signature)
+        })
+    }
+
+    /// Blames every line of the working tree version of `path`. Lines that are not
+    /// committed yet (including all lines of untracked files) are attributed to the
+    /// current user. The committed version is blamed only once per HEAD (see
+    /// `committed_blame`), saves only diff the working copy against it.
+    pub fn blame_working_copy<P: AsRef<Path>>(&self, path: P) -> Result<FileBlame, git2::Error> {
+        let path = path.as_ref();
+        let relative_path = self.relative_path(path)?;
+        let content = std::fs::read(path).map_err(|e| {
+            git2::Error::from_str(&format!("Failed to read '{}': {}", path.display(), e))
+        })?;
+        let line_count = content.iter().filter(|b| **b == b'\n').count()
+            + usize::from(!content.is_empty() && !content.ends_with(b"\n"));
+        let uncommitted = self.current_user().unwrap_or_else(|_| Attribution {
+            name: "Not Committed Yet".to_string(),
+            email: String::new(),
+            time: now(),
+        });
+
+        let head_tree = self.repo.head()?.peel_to_tree()?;
+        let blob = match head_tree.get_path(&relative_path) {
+            Ok(entry) => entry.to_object(&self.repo)?.peel_to_blob()?,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                return Ok(FileBlame {
+                    lines: vec![uncommitted; line_count],
+                });
+            }
+            Err(e) => return Err(e),
+        };
+        // The committed lines keep the commits of the cached blame, so only the changes of
+        // the working copy against HEAD are diffed on every save
+        let committed: Vec<Oid> = self
+            .committed_blame(&relative_path)?
+            .iter()
+            .flat_map(|hunk| std::iter::repeat_n(hunk.commit, hunk.lines))
+            .collect();
+        let mut opts = DiffOptions::new();
+        opts.context_lines(0);
+        let patch = Patch::from_blob_and_buffer(&blob, None, &content, None, Some(&mut opts))?;
+        let mut commits: Vec<Option<Oid>> = Vec::with_capacity(line_count);
+        let mut old_line = 0;
+        for hunk in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(hunk)?;
+            // A hunk without new lines starts after its `new_start`, others at it
+            let unchanged_until = hunk.new_start() as usize - usize::from(hunk.new_lines() > 0);
+            while commits.len() < unchanged_until {
+                commits.push(committed.get(old_line).copied());
+                old_line += 1;
+            }
+            commits.extend(std::iter::repeat_n(None, hunk.new_lines() as usize));
+            old_line += hunk.old_lines() as usize;
+        }
+        while commits.len() < line_count {
+            commits.push(committed.get(old_line).copied());
+            old_line += 1;
+        }
+
+        let mut authors: HashMap<Oid, Attribution> = HashMap::new();
+        let mut lines = Vec::with_capacity(line_count);
+        for commit_id in commits {
+            let attribution = match commit_id {
+                Some(id) => match authors.get(&id) {
+                    Some(author) => author.clone(),
+                    None => {
+                        let author =
+                            Attribution::from_signature(&self.repo.find_commit(id)?.author());
+                        authors.insert(id, author.clone());
+                        author
+                    }
+                },
+                None => uncommitted.clone(),
+            };
+            lines.push(attribution);
+        }
+        Ok(FileBlame { lines })
     }
 
     pub fn is_file_stale<P: AsRef<Path>>(
@@ -56,15 +586,12 @@ impl GitInspector {
         path: P,
         stale_days: u64,
     ) -> Result<bool, git2::Error> {
-        let relative_path = extract_path_from_src(path.as_ref())
-            .unwrap_or_else(|| path.as_ref().to_str().unwrap_or("").to_string());
-        let blame = self
-            .repo
-            .blame_file(relative_path.as_ref(), Some(&mut BlameOptions::new()))?;
+        let relative_path = self.relative_path(path)?;
+        let blame = self.committed_blame(&relative_path)?;
         let mut latest_time = 0;
 
         for hunk in blame.iter() {
-            let commit = self.repo.find_commit(hunk.final_commit_id())?;
+            let commit = self.repo.find_commit(hunk.commit)?;
             let time = commit.time().seconds();
             if time > latest_time {
                 latest_time = time;
@@ -79,26 +606,81 @@ impl GitInspector {
         Ok(age_days as u64 > stale_days)
     }
 
+    /// Number of lines per author in the committed version of `path`.
     pub fn file_commit_authors<P: AsRef<Path>>(
         &self,
         path: P,
     ) -> Result<HashMap<String, u32>, git2::Error> {
-        let relative_path = extract_path_from_src(path.as_ref())
-            .unwrap_or_else(|| path.as_ref().to_string_lossy().to_string());
-        let blame = self
-            .repo
-            .blame_file(relative_path.as_ref(), Some(&mut BlameOptions::new()))?;
+        let mut authors = HashMap::new();
+        for (author, lines) in self.line_authors(path)? {
+            *authors.entry(author.name).or_insert(0) += lines as u32;
+        }
+        Ok(authors)
+    }
+
+    /// Number of lines per (`.mailmap` resolved) author in the committed version of `path`.
+    pub fn line_authors<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<HashMap<Author, usize>, git2::Error> {
+        let relative_path = self.relative_path(path)?;
+        let blame = self.committed_blame(&relative_path)?;
+        let mailmap = self.repo.mailmap()?;
+        let mut commit_authors: HashMap<Oid, Author> = HashMap::new();
         let mut authors = HashMap::new();
 
         for hunk in blame.iter() {
-            let commit = self.repo.find_commit(hunk.final_commit_id())?;
-            let author = commit.author().name().unwrap_or("Unknown").to_string();
-            *authors.entry(author).or_insert(0) += 1;
+            let id = hunk.commit;
+            let author = match commit_authors.get(&id) {
+                Some(author) => author.clone(),
+                None => {
+                    let author = resolve_author(&mailmap, &self.repo.find_commit(id)?)?;
+                    commit_authors.insert(id, author.clone());
+                    author
+                }
+            };
+            *authors.entry(author).or_insert(0) += hunk.lines;
         }
 
         Ok(authors)
     }
 
+    /// Paths (relative to the working directory) of all text files committed in HEAD.
+    pub fn tracked_files(&self) -> Result<Vec<PathBuf>, git2::Error> {
+        let tree = self.repo.head()?.peel_to_tree()?;
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |directory, entry| {
+            let is_text = entry
+                .to_object(&self.repo)
+                .ok()
+                .and_then(|object| object.into_blob().ok())
+                .is_some_and(|blob| !blob.is_binary());
+            if let (true, Some(name)) = (is_text, entry.name()) {
+                files.push(Path::new(directory).join(name));
+            }
+            TreeWalkResult::Ok
+        })?;
+        Ok(files)
+    }
+
+    /// Time of the latest commit of every (`.mailmap` resolved) author reachable from HEAD.
+    pub fn last_commit_times(&self) -> Result<HashMap<Author, i64>, git2::Error> {
+        let mailmap = self.repo.mailmap()?;
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+
+        let mut last_commits: HashMap<Author, i64> = HashMap::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let time = commit.time().seconds();
+            let last_commit = last_commits
+                .entry(resolve_author(&mailmap, &commit)?)
+                .or_insert(time);
+            *last_commit = (*last_commit).max(time);
+        }
+        Ok(last_commits)
+    }
+
     pub fn most_frequent_author<P: AsRef<Path>>(
         &self,
         path: P,
@@ -119,3 +701,286 @@ impl GitInspector {
 // if let Some(author) = git_inspector.most_frequent_author(file)? {
 //     info!("🧙 Most edits on this file were made by: {}", author);
 // }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Writes `content` to `relative` inside the repository and commits it.
+    pub(crate) fn commit_file(repo: &Repository, relative: &str, content: &str) {
+        let signature = Signature::now("Tester", "tester@example.com").unwrap();
+        commit_file_as(repo, relative, content, &signature);
+    }
+
+    /// Like `commit_file`, authored and committed by `signature`.
+    pub(crate) fn commit_file_as(
+        repo: &Repository,
+        relative: &str,
+        content: &str,
+        signature: &Signature,
+    ) {
+        let workdir = repo.workdir().unwrap();
+        let path = workdir.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(relative)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            signature,
+            signature,
+            "commit",
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_discovery_in_checkout_below_src_directory() {
+        let dir = tempdir().unwrap();
+        let repo_dir = dir.path().join("src").join("project");
+        let repo = Repository::init(&repo_dir).unwrap();
+        commit_file(&repo, "src/lib.rs", "fn main() {}\n");
+        commit_file(&repo, "build.rs", "fn main() {}\n");
+
+        let file = repo_dir.join("src").join("lib.rs");
+        let inspector = GitInspector::new(&file).unwrap();
+        assert_eq!(
+            inspector.relative_path(&file).unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            inspector.most_frequent_author(&file).unwrap().as_deref(),
+            Some("Tester")
+        );
+
+        let outside_src = repo_dir.join("build.rs");
+        let inspector = GitInspector::new(&outside_src).unwrap();
+        assert!(!inspector.is_file_stale(&outside_src, 7).unwrap());
+        assert!(!inspector.is_file_changed(&outside_src).unwrap());
+    }
+
+    #[test]
+    fn test_discovery_in_worktree() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path().join("main")).unwrap();
+        commit_file(&repo, "src/lib.rs", "fn main() {}\n");
+        let worktree_dir = dir.path().join("feature");
+        repo.worktree("feature", &worktree_dir, None).unwrap();
+
+        let file = worktree_dir.join("src").join("lib.rs");
+        let inspector = GitInspector::new(&file).unwrap();
+        assert_eq!(
+            inspector.relative_path(&file).unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            inspector.most_frequent_author(&file).unwrap().as_deref(),
+            Some("Tester")
+        );
+    }
+
+    #[test]
+    fn test_diff_hunks() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "lib.rs", "a\nb\nc\nd\n");
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("main-base", &base, true).unwrap();
+        commit_file(&repo, "lib.rs", "a\nB\nc\nd\n");
+
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "a\nB\nc\nd\ne\nf\n").unwrap();
+        let inspector = GitInspector::new(&file).unwrap();
+        assert_eq!(
+            inspector.diff_hunks(&file, &DiffBase::Head).unwrap(),
+            vec![DiffHunk { start: 5, end: 6 }]
+        );
+        assert_eq!(
+            inspector
+                .diff_hunks(&file, &DiffBase::from("main-base"))
+                .unwrap(),
+            vec![DiffHunk { start: 2, end: 2 }, DiffHunk { start: 5, end: 6 }]
+        );
+
+        fs::write(&file, "a\nB\nd\n").unwrap();
+        let hunks = inspector.diff_hunks(&file, &DiffBase::Index).unwrap();
+        assert_eq!(hunks, vec![DiffHunk { start: 2, end: 3 }]);
+        assert!(hunks[0].overlaps(3, 10));
+        assert!(!hunks[0].overlaps(4, 10));
+
+        let untracked = dir.path().join("new.rs");
+        fs::write(&untracked, "x\ny\n").unwrap();
+        assert_eq!(
+            inspector.diff_hunks(&untracked, &DiffBase::Head).unwrap(),
+            vec![DiffHunk { start: 1, end: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_staged_files_are_read_from_index() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "src/lib.rs", "fn a() {}\n");
+
+        let file = dir.path().join("src").join("lib.rs");
+        fs::write(&file, "fn staged() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("src/lib.rs")).unwrap();
+        index.write().unwrap();
+        fs::write(&file, "fn unstaged() {}\n").unwrap();
+        fs::write(dir.path().join("untracked.rs"), "").unwrap();
+
+        let inspector = GitInspector::new(&file).unwrap();
+        let staged = inspector.list_staged_files().unwrap();
+        assert_eq!(staged, vec![PathBuf::from("src/lib.rs")]);
+        assert_eq!(
+            inspector.staged_source(&staged[0]).unwrap(),
+            "fn staged() {}\n"
+        );
+        assert!(inspector.staged_source(Path::new("untracked.rs")).is_err());
+        assert!(inspector.hooks_dir().ends_with(".git/hooks"));
+    }
+
+    #[test]
+    fn test_blame_working_copy() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Me").unwrap();
+        config.set_str("user.email", "me@example.com").unwrap();
+        commit_file(&repo, "lib.rs", "a\nb\nc\n");
+
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "a\nb\nB\nc\n").unwrap();
+        let inspector = GitInspector::new(&file).unwrap();
+        let me = inspector.current_user().unwrap();
+        let blame = inspector.blame_working_copy(&file).unwrap();
+        assert_eq!(blame.last_touched(1, 2).unwrap().name, "Tester");
+        assert!(!blame.last_touched(1, 2).unwrap().is_by(&me));
+        assert!(blame.last_touched(3, 4).unwrap().is_by(&me));
+        assert!(blame.last_touched(5, 6).is_none());
+        assert_eq!(blame.last_touched(4, 4).unwrap().age(), "today");
+
+        // Saving again only diffs against the cached committed blame
+        fs::write(&file, "X\nb\nc\n").unwrap();
+        let blame = inspector.blame_working_copy(&file).unwrap();
+        assert!(blame.last_touched(1, 1).unwrap().is_by(&me));
+        assert!(!blame.last_touched(2, 2).unwrap().is_by(&me));
+        assert!(!blame.last_touched(3, 3).unwrap().is_by(&me));
+        assert!(blame.last_touched(4, 4).is_none());
+        assert_eq!(inspector.cache.borrow().blames.len(), 1);
+
+        let untracked = dir.path().join("new.rs");
+        fs::write(&untracked, "x\ny").unwrap();
+        let blame = inspector.blame_working_copy(&untracked).unwrap();
+        assert!(blame.last_touched(2, 2).unwrap().is_by(&me));
+    }
+
+    #[test]
+    fn test_recent_changes() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "lib.rs", "a\nb\n");
+        commit_file(&repo, "lib.rs", "a\nB\nc\n");
+        commit_file(&repo, "other.rs", "x\n");
+
+        let inspector = GitInspector::new(dir.path()).unwrap();
+        let changes = inspector.recent_changes(1).unwrap();
+        let summary: Vec<(&Path, &[DiffHunk])> = changes
+            .iter()
+            .map(|c| (c.path.as_path(), c.hunks.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Path::new("other.rs"), &[DiffHunk { start: 1, end: 1 }][..]),
+                (Path::new("lib.rs"), &[DiffHunk { start: 2, end: 3 }][..]),
+                (Path::new("lib.rs"), &[DiffHunk { start: 1, end: 2 }][..]),
+            ]
+        );
+        assert_eq!(
+            inspector
+                .source_at(changes[2].commit, Path::new("lib.rs"))
+                .unwrap(),
+            "a\nb\n"
+        );
+    }
+
+    #[test]
+    fn test_recent_changes_stop_at_cutoff() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let old = Signature::new("Old", "old@example.com", &git2::Time::new(1_000_000, 0)).unwrap();
+        commit_file_as(&repo, "old.rs", "a\n", &old);
+        commit_file(&repo, "new.rs", "b\n");
+
+        let inspector = GitInspector::new(dir.path()).unwrap();
+        let paths = |days| -> Vec<PathBuf> {
+            let changes = inspector.recent_changes(days).unwrap();
+            changes.into_iter().map(|c| c.path).collect()
+        };
+        assert_eq!(paths(30), [PathBuf::from("new.rs")]);
+        assert_eq!(
+            paths(u64::MAX),
+            [PathBuf::from("new.rs"), PathBuf::from("old.rs")]
+        );
+    }
+
+    #[test]
+    fn test_blame_cache_follows_head() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "lib.rs", "a\nb\n");
+        let file = dir.path().join("lib.rs");
+
+        let inspector = GitInspector::new(&file).unwrap();
+        inspector.line_authors(&file).unwrap();
+        inspector.is_file_stale(&file, 30).unwrap();
+        assert_eq!(inspector.cache.borrow().blames.len(), 1);
+
+        let other = Signature::now("Other", "other@example.com").unwrap();
+        commit_file_as(&repo, "lib.rs", "a\nb\nc\n", &other);
+        let authors = inspector.file_commit_authors(&file).unwrap();
+        assert_eq!(authors.get("Tester"), Some(&2));
+        assert_eq!(authors.get("Other"), Some(&1));
+        assert_eq!(inspector.cache.borrow().blames.len(), 1);
+
+        assert!(!inspector.is_file_changed(&file).unwrap());
+        fs::write(&file, "changed\n").unwrap();
+        assert!(inspector.is_file_changed(&file).unwrap());
+        assert!(
+            !inspector
+                .is_file_changed(dir.path().join("missing.rs"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_path_outside_repository() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        commit_file(&repo, "lib.rs", "fn main() {}\n");
+
+        let inspector = GitInspector::new(dir.path().join("repo").join("lib.rs")).unwrap();
+        assert!(
+            inspector
+                .relative_path(dir.path().join("elsewhere.rs"))
+                .is_err()
+        );
+    }
+}
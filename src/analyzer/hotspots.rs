@@ -0,0 +1,156 @@
+//! Ranks the code that changes often *and* is complex, i.e. where refactoring pays off most.
+//!
+//! Every file and function gets the score `commits × cyclomatic complexity`, where commits
+//! are the commits of the analyzed period touching it and the complexity is measured on the
+//! current working tree. Function commits are counted on the version of the file in each
+//! commit, so functions that moved around are still attributed correctly.
+use std::collections::HashMap;
+use std::fs;
+
+use serde::Serialize;
+
+use crate::analyzer::complexity_inspector::{self, FunctionComplexity};
+use crate::analyzer::git::GitInspector;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hotspot {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Qualified function name; `None` when ranking whole files
+    pub function: Option<String>,
+    pub commits: usize,
+    pub complexity: usize,
+    pub score: usize,
+}
+
+/// Computes the hotspots of the last `days` days, highest score first.
+pub fn find_hotspots(
+    inspector: &GitInspector,
+    days: u64,
+    by_file: bool,
+) -> Result<Vec<Hotspot>, String> {
+    let workdir = inspector.workdir().map_err(|e| e.to_string())?;
+    let changes = inspector
+        .recent_changes(days)
+        .map_err(|e| format!("Failed to walk the history: {}", e))?;
+
+    let mut file_commits: HashMap<String, usize> = HashMap::new();
+    let mut function_commits: HashMap<(String, String), usize> = HashMap::new();
+    for change in changes
+        .iter()
+        .filter(|c| c.path.extension().is_some_and(|ext| ext == "rs"))
+    {
+        let path = change.path.to_string_lossy().replace('\\', "/");
+        *file_commits.entry(path.clone()).or_default() += 1;
+        if by_file {
+            continue;
+        }
+        // Files that do not parse at that commit simply do not count for their functions
+        let Some(functions) = inspector
+            .source_at(change.commit, &change.path)
+            .ok()
+            .and_then(|source| analyze_source(&source))
+        else {
+            continue;
+        };
+        for function in functions.iter().filter(|f| {
+            change
+                .hunks
+                .iter()
+                .any(|hunk| hunk.overlaps(f.start_line, f.end_line))
+        }) {
+            *function_commits
+                .entry((path.clone(), function.qualified_name.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    let mut hotspots = Vec::new();
+    for (path, commits) in &file_commits {
+        let Some(functions) = fs::read_to_string(workdir.join(path))
+            .ok()
+            .and_then(|source| analyze_source(&source))
+        else {
+            continue;
+        };
+        if by_file {
+            let complexity = functions.iter().map(|f| f.cyclomatic_complexity).sum();
+            hotspots.push(hotspot(path, None, *commits, complexity));
+            continue;
+        }
+        for function in functions {
+            if let Some(commits) =
+                function_commits.get(&(path.clone(), function.qualified_name.clone()))
+            {
+                hotspots.push(hotspot(
+                    path,
+                    Some(function.qualified_name),
+                    *commits,
+                    function.cyclomatic_complexity,
+                ));
+            }
+        }
+    }
+
+    hotspots.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.commits.cmp(&a.commits))
+            .then(a.path.cmp(&b.path))
+            .then(a.function.cmp(&b.function))
+    });
+    Ok(hotspots)
+}
+
+fn hotspot(path: &str, function: Option<String>, commits: usize, complexity: usize) -> Hotspot {
+    Hotspot {
+        path: path.to_string(),
+        function,
+        commits,
+        complexity,
+        score: commits * complexity,
+    }
+}
+
+fn analyze_source(source: &str) -> Option<Vec<FunctionComplexity>> {
+    syn::parse_file(source)
+        .ok()
+        .map(|syntax| complexity_inspector::analyze_file(&syntax))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::git::tests::commit_file;
+    use git2::Repository;
+    use tempfile::tempdir;
+
+    const CALM: &str = "fn calm() {}\n";
+    const BUSY_V1: &str = "fn busy(x: u8) {\n    if x > 0 {}\n}\n";
+    const BUSY_V2: &str = "fn busy(x: u8) {\n    if x > 0 {}\n    if x > 1 {}\n}\n";
+
+    #[test]
+    fn test_hotspots_rank_churn_times_complexity() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&repo, "src/lib.rs", &format!("{}{}", CALM, BUSY_V1));
+        commit_file(&repo, "src/lib.rs", &format!("{}{}", CALM, BUSY_V2));
+        commit_file(&repo, "src/other.rs", CALM);
+        commit_file(&repo, "README.md", "docs\n");
+
+        let inspector = GitInspector::new(dir.path()).unwrap();
+        let hotspots = find_hotspots(&inspector, 1, false).unwrap();
+        assert_eq!(
+            hotspots,
+            vec![
+                hotspot("src/lib.rs", Some("busy".into()), 2, 3),
+                hotspot("src/lib.rs", Some("calm".into()), 1, 1),
+                hotspot("src/other.rs", Some("calm".into()), 1, 1),
+            ]
+        );
+
+        let files = find_hotspots(&inspector, 1, true).unwrap();
+        assert_eq!(files[0], hotspot("src/lib.rs", None, 2, 4));
+        assert_eq!(files.len(), 2);
+    }
+}
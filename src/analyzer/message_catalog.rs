@@ -0,0 +1,552 @@
+//! Catalog of the texts Clippy says.
+//!
+//! Messages are identified by `<section>.<message>` (e.g. `complexity.warning`) and hold one
+//! text per grumpiness level. Texts may contain named placeholders like `{name}` that are
+//! filled in when a message is rendered. The built-in texts live in
+//! `assets/messages/default.toml`; a `message_catalog` file (TOML or JSON, same layout)
+//! overrides single texts or adds new ones.
+//!
+//! A text may also be a list of variants, of which one is picked per message shown (randomly
+//! or round-robin, see [`VariantSelector`]), never the same one twice in a row.
+//!
+//! The built-in texts are English; translations for other locales ship next to them
+//! (`assets/messages/<locale>.toml`) and fall back to English for anything they miss.
+
+use crate::config::{ConfigError, GrumpinessLevel, VariantSelection};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BUILTIN_CATALOG: &str = include_str!("../../assets/messages/default.toml");
+
+/// Locale of the built-in texts
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Translations shipped with Grumpy Clippy, by locale
+const BUILTIN_TRANSLATIONS: [(&str, &str); 2] = [
+    ("de", include_str!("../../assets/messages/de.toml")),
+    ("fr", include_str!("../../assets/messages/fr.toml")),
+];
+
+/// Personalities shipped with Grumpy Clippy, in English only
+const BUILTIN_PERSONALITIES: &str = include_str!("../../assets/messages/personalities.toml");
+
+/// Grumpiness levels every message has; other levels in a catalog are personalities
+const STANDARD_LEVELS: [&str; 3] = ["mild", "sarcastic", "rude"];
+
+/// Sections every translation has to cover completely
+pub const REQUIRED_SECTIONS: [&str; 5] = [
+    "clippy",
+    "complexity",
+    "function_size",
+    "git_is_stale",
+    "git_most_frequent_author",
+];
+
+/// Catalog used to render messages, the built-in one until [`init_catalog`] is called
+static CATALOG: Lazy<Mutex<MessageCatalog>> = Lazy::new(|| Mutex::new(MessageCatalog::builtin()));
+
+/// Layout of catalog files: section → message → grumpiness level → text(s)
+type CatalogFile = HashMap<String, HashMap<String, HashMap<String, Texts>>>;
+
+/// A single text or several variants of it
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Texts {
+    One(String),
+    Variants(Vec<String>),
+}
+
+impl Texts {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Texts::One(text) => vec![text],
+            Texts::Variants(texts) => texts,
+        }
+    }
+}
+
+/// Picks one of the variants of a message, never the one picked for it last time.
+#[derive(Debug, Clone)]
+pub struct VariantSelector {
+    selection: VariantSelection,
+    /// State of the random number generator (SplitMix64)
+    state: u64,
+    /// Variant picked last per message id and level
+    last: HashMap<String, usize>,
+}
+
+impl Default for VariantSelector {
+    fn default() -> Self {
+        Self::new(VariantSelection::default(), None)
+    }
+}
+
+impl VariantSelector {
+    /// Selector picking variants the given way. Random picks are reproducible for the same
+    /// `seed`; without one the current time is used.
+    pub fn new(selection: VariantSelection, seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        VariantSelector {
+            selection,
+            state: seed,
+            last: HashMap::new(),
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Index of the variant to show for `key` out of `count` variants.
+    fn pick(&mut self, key: &str, count: usize) -> usize {
+        let last = self.last.get(key).copied();
+        let index = match (count, last, &self.selection) {
+            (0 | 1, _, _) => 0,
+            (_, None, VariantSelection::RoundRobin) => 0,
+            (_, Some(last), VariantSelection::RoundRobin) => (last + 1) % count,
+            (_, None, VariantSelection::Random) => (self.next_random() % count as u64) as usize,
+            (_, Some(last), VariantSelection::Random) => {
+                // Draw from all variants but the last one
+                let index = (self.next_random() % (count - 1) as u64) as usize;
+                if index >= last { index + 1 } else { index }
+            }
+        };
+        self.last.insert(key.to_string(), index);
+        index
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    /// Text variants per message id and grumpiness level (lowercase)
+    messages: HashMap<String, HashMap<String, Vec<String>>>,
+    selector: VariantSelector,
+}
+
+impl MessageCatalog {
+    /// The texts shipped with Grumpy Clippy.
+    pub fn builtin() -> Self {
+        let file: CatalogFile =
+            toml::from_str(BUILTIN_CATALOG).expect("built-in message catalog is valid TOML");
+        Self::from_catalog_file(file)
+    }
+
+    /// The shipped translation for `locale` alone, without the English fallback.
+    fn translation(locale: &str) -> Option<Self> {
+        BUILTIN_TRANSLATIONS
+            .iter()
+            .find(|(name, _)| *name == locale)
+            .map(|(_, content)| {
+                let file: CatalogFile =
+                    toml::from_str(content).expect("built-in translations are valid TOML");
+                Self::from_catalog_file(file)
+            })
+    }
+
+    /// Built-in texts and personalities in `locale`, English where the translation has none.
+    pub fn localized(locale: &str) -> Self {
+        let mut catalog = Self::builtin();
+        let personalities: CatalogFile =
+            toml::from_str(BUILTIN_PERSONALITIES).expect("built-in personalities are valid TOML");
+        catalog.extend(Self::from_catalog_file(personalities));
+        if let Some(translation) = Self::translation(locale) {
+            catalog.extend(translation);
+        }
+        catalog
+    }
+
+    /// Reads a catalog from a `.json` file, or a TOML file for any other extension.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Err(ConfigError::FileNotFoundError(path.to_path_buf()));
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))?;
+        let file: CatalogFile = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)
+                .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))?
+        } else {
+            toml::from_str(&content)
+                .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))?
+        };
+        Ok(Self::from_catalog_file(file))
+    }
+
+    fn from_catalog_file(file: CatalogFile) -> Self {
+        let messages = file
+            .into_iter()
+            .flat_map(|(section, messages)| {
+                messages
+                    .into_iter()
+                    .map(move |(message, texts)| (format!("{}.{}", section, message), texts))
+            })
+            .map(|(id, texts)| {
+                let texts = texts
+                    .into_iter()
+                    .map(|(level, texts)| (level.to_lowercase(), texts.into_vec()))
+                    .filter(|(_, texts)| !texts.is_empty())
+                    .collect();
+                (id, texts)
+            })
+            .collect();
+        MessageCatalog {
+            messages,
+            selector: VariantSelector::default(),
+        }
+    }
+
+    /// Built-in catalog in `locale`, overridden and extended by the catalog file at `path`.
+    pub fn with_overrides(locale: &str, path: &Path) -> Result<Self, ConfigError> {
+        let mut catalog = Self::localized(locale);
+        catalog.extend(Self::from_file(path)?);
+        Ok(catalog)
+    }
+
+    /// Names of all grumpiness levels and personalities with texts in this catalog.
+    pub fn personalities(&self) -> Vec<String> {
+        let mut personalities: Vec<String> = STANDARD_LEVELS.map(str::to_string).to_vec();
+        let mut declared: Vec<String> = self
+            .messages
+            .values()
+            .flat_map(|texts| texts.keys())
+            .filter(|level| !STANDARD_LEVELS.contains(&level.as_str()))
+            .cloned()
+            .collect();
+        declared.sort();
+        declared.dedup();
+        personalities.extend(declared);
+        personalities
+    }
+
+    /// Checks that the catalog has texts for `level`, listing the available ones otherwise.
+    pub fn validate_level(&self, level: &GrumpinessLevel) -> Result<(), ConfigError> {
+        let personalities = self.personalities();
+        let name = level.to_string().to_lowercase();
+        if personalities.contains(&name) {
+            Ok(())
+        } else {
+            Err(ConfigError::UnknownPersonality(name, personalities))
+        }
+    }
+
+    /// Picks text variants with `selector` from now on.
+    pub fn select_variants(&mut self, selector: VariantSelector) {
+        self.selector = selector;
+    }
+
+    /// Adds the texts of `other`, replacing the variants for the same message id and level.
+    pub fn extend(&mut self, other: MessageCatalog) {
+        for (id, texts) in other.messages {
+            self.messages.entry(id).or_default().extend(texts);
+        }
+    }
+
+    /// Text of message `id` for `level` with its placeholders filled from `args`. Of several
+    /// variants, the selector picks one.
+    ///
+    /// Falls back to the mild text if the level has none, and to the id itself for unknown
+    /// messages, so a broken catalog never silences Clippy. Unknown placeholders are kept.
+    pub fn render(
+        &mut self,
+        id: &str,
+        level: &GrumpinessLevel,
+        args: &[(&str, &dyn Display)],
+    ) -> String {
+        let level = level.to_string().to_lowercase();
+        let Some((level, texts)) = self.messages.get(id).and_then(|texts| {
+            texts
+                .get_key_value(&level)
+                .or_else(|| texts.get_key_value("mild"))
+        }) else {
+            return id.to_string();
+        };
+        let index = self
+            .selector
+            .pick(&format!("{}/{}", id, level), texts.len());
+        fill_placeholders(&texts[index], args)
+    }
+}
+
+/// Replaces every `{name}` of `text` with its value from `args` in a single pass, so that
+/// values containing braces (e.g. an author named `{max}`) are not substituted again.
+fn fill_placeholders(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let (_, value) = args.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Whether Clippy speaks `locale`, either natively or by a shipped translation.
+pub fn is_supported_locale(locale: &str) -> bool {
+    locale == DEFAULT_LOCALE || BUILTIN_TRANSLATIONS.iter().any(|(name, _)| *name == locale)
+}
+
+/// All locales Clippy speaks.
+pub fn supported_locales() -> Vec<&'static str> {
+    std::iter::once(DEFAULT_LOCALE)
+        .chain(BUILTIN_TRANSLATIONS.iter().map(|(name, _)| *name))
+        .collect()
+}
+
+/// Messages (`<id> (<level>)`) of the [`REQUIRED_SECTIONS`] the translation for `locale` lacks.
+/// English is complete by definition, unknown locales lack everything.
+pub fn missing_translations(locale: &str) -> Vec<String> {
+    if locale == DEFAULT_LOCALE {
+        return vec![];
+    }
+    let translation = MessageCatalog::translation(locale).unwrap_or_default();
+    let mut missing: Vec<String> = MessageCatalog::builtin()
+        .messages
+        .into_iter()
+        .filter(|(id, _)| {
+            REQUIRED_SECTIONS
+                .iter()
+                .any(|section| id.split('.').next() == Some(section))
+        })
+        .flat_map(|(id, texts)| {
+            let translated = translation.messages.get(&id).cloned().unwrap_or_default();
+            texts
+                .into_keys()
+                .filter(|level| STANDARD_LEVELS.contains(&level.as_str()))
+                .filter(move |level| !translated.contains_key(level))
+                .map(move |level| format!("{} ({})", id, level))
+        })
+        .collect();
+    missing.sort();
+    missing
+}
+
+/// Locale from a POSIX locale name like `LANG=de_DE.UTF-8`; `C` and `POSIX` mean English.
+pub fn locale_from_lang(lang: &str) -> Option<String> {
+    let language = lang
+        .split(['_', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match language.as_str() {
+        "" | "c" | "posix" => None,
+        _ => Some(language),
+    }
+}
+
+/// Replaces the catalog used to render messages.
+pub fn init_catalog(catalog: MessageCatalog) {
+    *CATALOG.lock().unwrap() = catalog;
+}
+
+/// Renders message `id` from the current catalog, see [`MessageCatalog::render`].
+pub fn message(id: &str, level: &GrumpinessLevel, args: &[(&str, &dyn Display)]) -> String {
+    CATALOG.lock().unwrap().render(id, level, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builtin_catalog_renders_placeholders() {
+        let mut catalog = MessageCatalog::builtin();
+        assert_eq!(
+            catalog.render(
+                "complexity.warning",
+                &GrumpinessLevel::Mild,
+                &[("name", &"parse"), ("complexity", &12), ("max", &10)]
+            ),
+            "Function 'parse': Cyclomatic complexity too high (12 > 10). Consider simplifying it."
+        );
+        assert_eq!(
+            catalog.render(
+                "blame.last_touched",
+                &GrumpinessLevel::Mild,
+                &[("author", &"{age} {nope}"), ("age", &"2 days ago")]
+            ),
+            "   ↳ last touched by {age} {nope} 2 days ago"
+        );
+        assert!(catalog.messages.values().all(|texts| texts.len() == 3));
+        assert_eq!(
+            catalog.render("no.such_message", &GrumpinessLevel::Rude, &[]),
+            "no.such_message"
+        );
+    }
+
+    #[test]
+    fn test_shipped_translations_are_complete() {
+        for locale in supported_locales() {
+            assert_eq!(
+                missing_translations(locale),
+                Vec::<String>::new(),
+                "{}",
+                locale
+            );
+        }
+        assert_eq!(missing_translations("xx").len(), 18);
+
+        let mut german = MessageCatalog::localized("de");
+        assert_eq!(
+            german.render("git_is_stale.info", &GrumpinessLevel::Rude, &[]),
+            "Git: Die Datei setzt Staub an. Schläfst du an der Tastatur?"
+        );
+        // Untranslated messages fall back to English
+        assert_eq!(
+            german.render("pre_commit.passed", &GrumpinessLevel::Mild, &[]),
+            "✅ No deny findings in the staged files"
+        );
+    }
+
+    #[test]
+    fn test_personalities_from_catalog_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("messages.toml");
+        fs::write(
+            &path,
+            "[complexity.warning]\nyoda = \"Complex, '{name}' is. Simplify it, you must.\"\n",
+        )
+        .unwrap();
+        let mut catalog = MessageCatalog::with_overrides(DEFAULT_LOCALE, &path).unwrap();
+        assert_eq!(
+            catalog.personalities(),
+            [
+                "mild",
+                "sarcastic",
+                "rude",
+                "drill_sergeant",
+                "passive_aggressive",
+                "pirate",
+                "professional",
+                "yoda"
+            ]
+        );
+
+        let yoda = GrumpinessLevel::Personality("yoda".into());
+        assert!(catalog.validate_level(&yoda).is_ok());
+        assert_eq!(
+            catalog.render("complexity.warning", &yoda, &[("name", &"parse")]),
+            "Complex, 'parse' is. Simplify it, you must."
+        );
+        // Messages the personality does not cover are mild
+        assert_eq!(
+            catalog.render("git_is_stale.info", &yoda, &[]),
+            "Git: Hey there! Just a heads-up: file hasn’t been updated in a while."
+        );
+
+        let error = catalog
+            .validate_level(&GrumpinessLevel::Personality("vader".into()))
+            .unwrap_err();
+        assert!(error.to_string().contains("vader"));
+        assert!(error.to_string().contains("pirate, professional, yoda"));
+    }
+
+    #[test]
+    fn test_variants_do_not_repeat() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("messages.toml");
+        fs::write(&path, "[clippy.success]\nmild = [\"a\", \"b\", \"c\"]\n").unwrap();
+        let mut catalog = MessageCatalog::from_file(&path).unwrap();
+        let render = |catalog: &mut MessageCatalog| {
+            (0..30)
+                .map(|_| catalog.render("clippy.success", &GrumpinessLevel::Mild, &[]))
+                .collect::<Vec<String>>()
+        };
+
+        catalog.select_variants(VariantSelector::new(VariantSelection::RoundRobin, None));
+        assert_eq!(render(&mut catalog)[..4], ["a", "b", "c", "a"]);
+
+        catalog.select_variants(VariantSelector::new(VariantSelection::Random, Some(7)));
+        let first = render(&mut catalog);
+        assert!(first.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(
+            ["a", "b", "c"]
+                .iter()
+                .all(|text| first.contains(&text.to_string()))
+        );
+        catalog.select_variants(VariantSelector::new(VariantSelection::Random, Some(7)));
+        assert_eq!(render(&mut catalog), first);
+    }
+
+    #[test]
+    fn test_locale_from_lang() {
+        assert_eq!(locale_from_lang("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(locale_from_lang("fr").as_deref(), Some("fr"));
+        assert_eq!(locale_from_lang("C.UTF-8"), None);
+        assert_eq!(locale_from_lang(""), None);
+    }
+
+    #[test]
+    fn test_catalog_file_overrides_and_extends() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("messages.json");
+        fs::write(
+            &path,
+            r#"{
+                "clippy": { "success": { "rude": "Fine. {who} got lucky." } },
+                "custom": { "hello": { "mild": "Hello {who}" } }
+            }"#,
+        )
+        .unwrap();
+
+        let mut catalog = MessageCatalog::with_overrides(DEFAULT_LOCALE, &path).unwrap();
+        let who: [(&str, &dyn Display); 1] = [("who", &"you")];
+        assert_eq!(
+            catalog.render("clippy.success", &GrumpinessLevel::Rude, &who),
+            "Fine. you got lucky."
+        );
+        assert_eq!(
+            catalog.render("clippy.success", &GrumpinessLevel::Mild, &who),
+            "✅ cargo clippy successful"
+        );
+        assert_eq!(
+            catalog.render("custom.hello", &GrumpinessLevel::Sarcastic, &who),
+            "Hello you"
+        );
+
+        let mut catalog = MessageCatalog::with_overrides("de", &path).unwrap();
+        assert_eq!(
+            catalog.render("clippy.success", &GrumpinessLevel::Mild, &who),
+            "✅ cargo clippy erfolgreich"
+        );
+        assert_eq!(
+            catalog.render("clippy.success", &GrumpinessLevel::Rude, &who),
+            "Fine. you got lucky."
+        );
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            MessageCatalog::from_file(&path),
+            Err(ConfigError::InvalidFile(_, _))
+        ));
+    }
+}
@@ -1,100 +1,194 @@
+//! What Clippy says, rendered from the message catalog (see `message_catalog`).
+
 pub mod clippy {
+    use crate::analyzer::message_catalog::message;
     use crate::config::GrumpinessLevel;
 
-    pub fn success(level: &GrumpinessLevel) -> &'static str {
-        match level {
-            GrumpinessLevel::Mild => "✅ cargo clippy successful",
-            GrumpinessLevel::Sarcastic => "✅🙈 Oh, you did not break anything. Strange!",
-            GrumpinessLevel::Rude => {
-                "✅🙄 Oh, you managed not to break anything? Well, there is a first time for everything."
-            }
-        }
+    pub fn success(level: &GrumpinessLevel) -> String {
+        message("clippy.success", level, &[])
     }
 
-    pub fn failure(level: &GrumpinessLevel) -> &'static str {
-        match level {
-            GrumpinessLevel::Mild => "❌ Clippy failed (see terminal for details)",
-            GrumpinessLevel::Sarcastic => "❌🙄 Oh, you did break something (as usual):",
-            GrumpinessLevel::Rude => "❌💣 Of course you broke something—how utterly predictable.",
-        }
+    pub fn failure(level: &GrumpinessLevel) -> String {
+        message("clippy.failure", level, &[])
     }
 }
 
 pub mod complexity {
+    use crate::analyzer::message_catalog::message;
     pub use crate::config::GrumpinessLevel;
 
     pub fn warning(level: &GrumpinessLevel, name: &str, complexity: usize, max: u8) -> String {
-        match level {
-            GrumpinessLevel::Mild => format!(
-                "Function '{}': Cyclomatic complexity too high ({} > {}). Consider simplifying it.",
-                name, complexity, max
-            ),
-            GrumpinessLevel::Sarcastic => format!(
-                "Function '{}': Wow, cyclomatic complexity ({} > {})! Are you trying to write a novel?",
-                name, complexity, max
-            ),
-            GrumpinessLevel::Rude => format!(
-                "Function '{}': Cyclomatic complexity ({} > {})? What is this monstrosity?",
-                name, complexity, max
-            ),
-        }
+        message(
+            "complexity.warning",
+            level,
+            &[("name", &name), ("complexity", &complexity), ("max", &max)],
+        )
     }
 }
 
 pub mod function_size {
+    use crate::analyzer::message_catalog::message;
     use crate::config::GrumpinessLevel;
 
     pub fn warning(level: &GrumpinessLevel, name: &str, size: usize, max: u8) -> String {
-        match level {
-            GrumpinessLevel::Mild => format!(
-                "Function '{}': Too many lines ({} > {}). Consider refactoring.",
-                name, size, max
-            ),
-            GrumpinessLevel::Sarcastic => format!(
-                "Function '{}': Wow, {} lines ({} > {})! Are you writing a novel?",
-                name, size, size, max
-            ),
-            GrumpinessLevel::Rude => format!(
-                "Function '{}': {} lines ({} > {})? This is absurd!",
-                name, size, size, max
-            ),
-        }
+        message(
+            "function_size.warning",
+            level,
+            &[("name", &name), ("size", &size), ("max", &max)],
+        )
     }
 }
 
 pub mod git_is_stale {
+    use crate::analyzer::message_catalog::message;
     use crate::config::GrumpinessLevel;
 
     pub fn info(level: &GrumpinessLevel) -> String {
-        match level {
-            GrumpinessLevel::Mild => {
-                format!("Git: Hey there! Just a heads-up: file hasn’t been updated in a while.",)
-            }
-            GrumpinessLevel::Sarcastic => {
-                format!("Git: file looks stale. Consider revisiting it.")
-            }
-            GrumpinessLevel::Rude => {
-                format!("Git: file is gathering dust. Are you asleep at the keyboard?",)
-            }
-        }
+        message("git_is_stale.info", level, &[])
     }
 }
 
 pub mod git_most_frequent_author {
+    use crate::analyzer::message_catalog::message;
     use crate::config::GrumpinessLevel;
 
     pub fn info(level: &GrumpinessLevel, author: &str) -> String {
-        match level {
-            GrumpinessLevel::Mild => {
-                format!("Git: file mostly edited by our star `{}`!", author)
-            }
-            GrumpinessLevel::Sarcastic => format!(
-                "Git: file mostly authored by `{}`. Check if they're still around.",
-                author
-            ),
-            GrumpinessLevel::Rude => {
-                format!("Git: Looks like here is {}'s personal playground.", author)
-            }
-        }
+        message(
+            "git_most_frequent_author.info",
+            level,
+            &[("author", &author)],
+        )
+    }
+}
+
+pub mod custom_rule {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn violation(level: &GrumpinessLevel, rule: &str, message_text: &str) -> String {
+        message(
+            "custom_rule.violation",
+            level,
+            &[("rule", &rule), ("message", &message_text)],
+        )
+    }
+}
+
+pub mod codeowners {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn owned_by(level: &GrumpinessLevel, owners: &str) -> String {
+        message("codeowners.owned_by", level, &[("owners", &owners)])
+    }
+
+    pub fn unowned(level: &GrumpinessLevel) -> String {
+        message("codeowners.unowned", level, &[])
+    }
+
+    pub fn finding_owners(level: &GrumpinessLevel, owners: &str) -> String {
+        message("codeowners.finding_owners", level, &[("owners", &owners)])
+    }
+}
+
+pub mod blame {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn last_touched(level: &GrumpinessLevel, author: &str, age: &str) -> String {
+        message(
+            "blame.last_touched",
+            level,
+            &[("author", &author), ("age", &age)],
+        )
+    }
+}
+
+pub mod repeat_offense {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn ignored(level: &GrumpinessLevel, count: u32) -> String {
+        message("repeat_offense.ignored", level, &[("count", &count)])
+    }
+}
+
+pub mod config_reload {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn applied(level: &GrumpinessLevel) -> String {
+        message("config_reload.applied", level, &[])
+    }
+
+    pub fn invalid(level: &GrumpinessLevel, error: &str) -> String {
+        message("config_reload.invalid", level, &[("error", &error)])
+    }
+
+    pub fn invalid_rules(level: &GrumpinessLevel, error: &str) -> String {
+        message("config_reload.invalid_rules", level, &[("error", &error)])
+    }
+}
+
+pub mod pre_commit {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn blocked(level: &GrumpinessLevel, count: usize) -> String {
+        message("pre_commit.blocked", level, &[("count", &count)])
+    }
+
+    pub fn passed(level: &GrumpinessLevel) -> String {
+        message("pre_commit.passed", level, &[])
+    }
+}
+
+pub mod commit_message {
+    use crate::analyzer::message_catalog::message;
+    use crate::config::GrumpinessLevel;
+
+    pub fn missing_type(level: &GrumpinessLevel, types: &str) -> String {
+        message("commit_message.missing_type", level, &[("types", &types)])
+    }
+
+    pub fn subject_too_long(level: &GrumpinessLevel, length: usize, max: usize) -> String {
+        message(
+            "commit_message.subject_too_long",
+            level,
+            &[("length", &length), ("max", &max)],
+        )
+    }
+
+    pub fn missing_blank_line(level: &GrumpinessLevel) -> String {
+        message("commit_message.missing_blank_line", level, &[])
+    }
+
+    pub fn body_line_too_long(
+        level: &GrumpinessLevel,
+        line: usize,
+        length: usize,
+        max: usize,
+    ) -> String {
+        message(
+            "commit_message.body_line_too_long",
+            level,
+            &[("line", &line), ("length", &length), ("max", &max)],
+        )
+    }
+
+    pub fn missing_issue_key(level: &GrumpinessLevel, pattern: &str) -> String {
+        message(
+            "commit_message.missing_issue_key",
+            level,
+            &[("pattern", &pattern)],
+        )
+    }
+
+    pub fn blocked(level: &GrumpinessLevel, count: usize) -> String {
+        message("commit_message.blocked", level, &[("count", &count)])
+    }
+
+    pub fn passed(level: &GrumpinessLevel) -> String {
+        message("commit_message.passed", level, &[])
     }
 }
@@ -1,5 +1,17 @@
 pub mod actions;
+pub mod adaptive;
+pub mod baseline;
+pub mod codeowners;
+pub mod commit_message;
 pub mod complexity_inspector;
 pub mod custom_rules;
+pub mod explain;
+pub mod finding;
+pub mod fix;
 pub mod git;
+pub mod hotspots;
+pub mod message_catalog;
 pub mod messages;
+pub mod ownership;
+pub mod rule_harness;
+pub mod script_rules;
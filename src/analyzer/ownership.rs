@@ -0,0 +1,202 @@
+//! Who owns which part of the code base, aggregated per directory from `git blame`.
+//!
+//! Authors are merged according to `.mailmap`. The bus factor is the smallest number of
+//! authors that together own more than half of the lines, i.e. how many people have to
+//! leave before most of the directory is unknown territory.
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::analyzer::git::{Author, GitInspector};
+use crate::warning;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DirectoryOwnership {
+    /// Directory relative to the repository root (`.` for the root itself)
+    pub path: String,
+    pub lines: usize,
+    pub authors: usize,
+    pub top_author: String,
+    /// Share of the lines owned by the top author, in percent
+    pub top_author_share: f64,
+    pub bus_factor: usize,
+    /// Authors owning lines here whose latest commit is older than the inactivity period
+    pub inactive_authors: Vec<String>,
+}
+
+/// Aggregates the blame of all tracked text files below `roots` (relative to the
+/// repository root; all files if empty). With `depth`, directories are cut off after that
+/// many components, so e.g. `depth = 1` reports `src` as a whole. Files deleted in the
+/// working tree are left out, files that cannot be blamed are reported and skipped.
+pub fn ownership_report(
+    inspector: &GitInspector,
+    roots: &[PathBuf],
+    depth: Option<usize>,
+    inactive_months: u64,
+) -> Result<Vec<DirectoryOwnership>, String> {
+    let workdir = inspector.workdir().map_err(|e| e.to_string())?;
+    let files = inspector
+        .tracked_files()
+        .map_err(|e| format!("Failed to list tracked files: {}", e))?;
+
+    let mut directories: BTreeMap<PathBuf, HashMap<Author, usize>> = BTreeMap::new();
+    for file in files.iter().filter(|file| {
+        (roots.is_empty() || roots.iter().any(|root| file.starts_with(root)))
+            && workdir.join(file).exists()
+    }) {
+        let authors = match inspector.line_authors(workdir.join(file)) {
+            Ok(authors) => authors,
+            Err(e) => {
+                warning!("Skipping '{}', failed to blame it: {}", file.display(), e);
+                continue;
+            }
+        };
+        let directory = directory_of(file, depth);
+        let lines = directories.entry(directory).or_default();
+        for (author, count) in authors {
+            *lines.entry(author).or_default() += count;
+        }
+    }
+
+    let last_commits = inspector
+        .last_commit_times()
+        .map_err(|e| format!("Failed to walk the history: {}", e))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let inactive_period = inactive_months.saturating_mul(30 * 86400);
+    let inactive_since = now.saturating_sub(i64::try_from(inactive_period).unwrap_or(i64::MAX));
+
+    Ok(directories
+        .into_iter()
+        .filter_map(|(directory, authors)| {
+            let mut authors: Vec<(Author, usize)> = authors.into_iter().collect();
+            authors.sort_by(|(a, a_lines), (b, b_lines)| b_lines.cmp(a_lines).then(a.cmp(b)));
+            let lines: usize = authors.iter().map(|(_, count)| count).sum();
+            let (top_author, top_lines) = authors.first()?;
+            Some(DirectoryOwnership {
+                path: directory.to_string_lossy().replace('\\', "/"),
+                lines,
+                authors: authors.len(),
+                top_author: top_author.to_string(),
+                top_author_share: (*top_lines as f64 * 1000.0 / lines as f64).round() / 10.0,
+                bus_factor: bus_factor(&authors, lines),
+                inactive_authors: authors
+                    .iter()
+                    .filter(|(author, _)| {
+                        last_commits
+                            .get(author)
+                            .is_none_or(|time| *time < inactive_since)
+                    })
+                    .map(|(author, _)| author.to_string())
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+fn directory_of(file: &Path, depth: Option<usize>) -> PathBuf {
+    let directory = file.parent().unwrap_or(Path::new(""));
+    let components = directory
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)));
+    let directory: PathBuf = match depth {
+        Some(depth) => components.take(depth).collect(),
+        None => components.collect(),
+    };
+    if directory.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        directory
+    }
+}
+
+/// Smallest number of authors (sorted by lines, descending) owning more than half the lines.
+fn bus_factor(authors: &[(Author, usize)], lines: usize) -> usize {
+    let mut owned = 0;
+    for (index, (_, count)) in authors.iter().enumerate() {
+        owned += count;
+        if owned * 2 > lines {
+            return index + 1;
+        }
+    }
+    authors.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::git::tests::commit_file_as;
+    use git2::{Repository, Signature, Time};
+    use tempfile::tempdir;
+
+    fn signature(name: &str, email: &str, days_ago: i64) -> Signature<'static> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        Signature::new(name, email, &Time::new(now - days_ago * 86400, 0)).unwrap()
+    }
+
+    #[test]
+    fn test_ownership_report() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let old_timer = signature("Old Timer", "old@example.com", 400);
+        let alice = signature("Alice", "alice@example.com", 10);
+        let alias = signature("ali", "alice@home.example", 5);
+        commit_file_as(&repo, "src/a/lib.rs", "1\n2\n3\n4\n", &old_timer);
+        commit_file_as(&repo, "src/a/mod.rs", "1\n2\n", &alice);
+        commit_file_as(&repo, "src/b.rs", "1\n2\n3\n", &alias);
+        commit_file_as(
+            &repo,
+            ".mailmap",
+            "Alice <alice@example.com> ali <alice@home.example>\n",
+            &alice,
+        );
+
+        let inspector = GitInspector::new(dir.path()).unwrap();
+        let report = ownership_report(&inspector, &[PathBuf::from("src")], None, 6).unwrap();
+        assert_eq!(
+            report,
+            vec![
+                DirectoryOwnership {
+                    path: "src".into(),
+                    lines: 3,
+                    authors: 1,
+                    top_author: "Alice".into(),
+                    top_author_share: 100.0,
+                    bus_factor: 1,
+                    inactive_authors: vec![],
+                },
+                DirectoryOwnership {
+                    path: "src/a".into(),
+                    lines: 6,
+                    authors: 2,
+                    top_author: "Old Timer".into(),
+                    top_author_share: 66.7,
+                    bus_factor: 1,
+                    inactive_authors: vec!["Old Timer".into()],
+                },
+            ]
+        );
+
+        let report = ownership_report(&inspector, &[], Some(1), 6).unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[1].path, "src");
+        assert_eq!(report[1].lines, 9);
+        assert_eq!(report[1].top_author, "Alice");
+        assert_eq!(report[1].top_author_share, 55.6);
+        assert_eq!(report[1].inactive_authors, vec!["Old Timer"]);
+
+        commit_file_as(&repo, "src/logo.png", "\0\u{1}binary\n", &alice);
+        std::fs::remove_file(dir.path().join("src/b.rs")).unwrap();
+        let report =
+            ownership_report(&inspector, &[PathBuf::from("src")], Some(1), u64::MAX).unwrap();
+        assert_eq!(report[0].lines, 6);
+        assert!(report[0].inactive_authors.is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+//! Fixture based test runner for custom rules.
+//!
+//! A fixture is a `.rs` file whose lines are annotated with the findings they are expected
+//! to produce, e.g. `println!("hi"); //~ WARN forbid_word`, or `//~ DENY <rule_id>` for
+//! findings of rules with `severity = "deny"`. Several annotations may follow each other on
+//! one line. Annotations are blanked out before the fixture is analyzed so
+//! they cannot trigger rules themselves.
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::analyzer::actions::analyze_source_complexity;
+use crate::analyzer::custom_rules::{RuleConfig, apply_rules};
+use crate::analyzer::finding::Severity;
+use crate::config::GrumpinessLevel;
+
+const ANNOTATION: &str = "//~";
+
+/// A finding expected (or produced) at a given line of a fixture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectation {
+    /// 1-based line; `None` for findings that cannot be located in the file
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub rule: String,
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: ", line)?,
+            None => write!(f, "file: ")?,
+        }
+        let severity = match self.severity {
+            Severity::Warn => "WARN",
+            Severity::Deny => "DENY",
+        };
+        write!(f, "{} {}", severity, self.rule)
+    }
+}
+
+/// Outcome of running one fixture.
+#[derive(Debug)]
+pub struct FixtureReport {
+    pub missing: Vec<Expectation>,
+    pub unexpected: Vec<Expectation>,
+}
+
+impl FixtureReport {
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Parses all `//~ WARN <rule_id>` and `//~ DENY <rule_id>` annotations of a fixture.
+pub fn parse_expectations(source: &str) -> Result<Vec<Expectation>, String> {
+    let mut expectations = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        for annotation in line.split(ANNOTATION).skip(1) {
+            let mut words = annotation.split_whitespace();
+            let severity = match words.next() {
+                Some("WARN") => Some(Severity::Warn),
+                Some("DENY") => Some(Severity::Deny),
+                _ => None,
+            };
+            match (severity, words.next(), words.next()) {
+                (Some(severity), Some(rule), None) => expectations.push(Expectation {
+                    line: Some(index + 1),
+                    severity,
+                    rule: rule.to_string(),
+                }),
+                _ => {
+                    return Err(format!(
+                        "Invalid annotation on line {}: expected '{} WARN <rule_id>' or '{} DENY <rule_id>'",
+                        index + 1,
+                        ANNOTATION,
+                        ANNOTATION
+                    ));
+                }
+            }
+        }
+    }
+    Ok(expectations)
+}
+
+/// Replaces every annotation with spaces, keeping byte offsets and line numbers intact.
+fn strip_annotations(source: &str) -> String {
+    source
+        .split_inclusive('\n')
+        .map(|line| match line.find(ANNOTATION) {
+            Some(start) => {
+                let end = line.trim_end_matches(['\n', '\r']).len();
+                format!(
+                    "{}{}{}",
+                    &line[..start],
+                    " ".repeat(end - start),
+                    &line[end..]
+                )
+            }
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// Runs the complexity inspector and the custom rules against a fixture and compares the
+/// produced findings with its annotations. A finding only matches an annotation of the same
+/// severity, so a rule reporting `warn` instead of `deny` shows up as missing and unexpected.
+pub fn check_fixture(
+    path: &Path,
+    grumpiness_level: &GrumpinessLevel,
+    max_function_size: &u8,
+    max_cyclomatic_complexity: &u8,
+    rules: &[RuleConfig],
+) -> Result<FixtureReport, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read fixture '{}': {}", path.display(), e))?;
+    let mut missing = parse_expectations(&source)?;
+    let code = strip_annotations(&source);
+
+    let (_, mut findings) = analyze_source_complexity(
+        &code,
+        grumpiness_level,
+        max_function_size,
+        max_cyclomatic_complexity,
+    )?;
+    let (_, rule_findings) = apply_rules(rules.to_vec(), &code)?;
+    findings.extend(rule_findings);
+
+    let mut unexpected = Vec::new();
+    for finding in findings {
+        let matched = missing.iter().position(|expected| {
+            expected.rule == finding.rule
+                && expected.severity == finding.severity
+                && (finding.line.is_none() || expected.line == finding.line)
+        });
+        match matched {
+            Some(index) => {
+                missing.remove(index);
+            }
+            None => unexpected.push(Expectation {
+                line: finding.line,
+                severity: finding.severity,
+                rule: finding.rule,
+            }),
+        }
+    }
+
+    Ok(FixtureReport {
+        missing,
+        unexpected,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn forbid_println() -> RuleConfig {
+        RuleConfig {
+            name: "forbid_word".into(),
+            enabled: true,
+            option: Some("println!".into()),
+            ..Default::default()
+        }
+    }
+
+    fn run_fixture(source: &str) -> FixtureReport {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fixture.rs");
+        fs::File::create(&path)
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        check_fixture(&path, &GrumpinessLevel::Mild, &32, &2, &[forbid_println()]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_expectations() {
+        let source = "fn a() {}\nlet x = 1; //~ WARN complexity //~ WARN function_size\n";
+        let expectations = parse_expectations(source).unwrap();
+        assert_eq!(expectations.len(), 2);
+        assert_eq!(expectations[1].line, Some(2));
+        assert_eq!(expectations[1].rule, "function_size");
+        assert_eq!(expectations[1].severity, Severity::Warn);
+        let deny = parse_expectations("let x = 1; //~ DENY forbid_word\n").unwrap();
+        assert_eq!(deny[0].severity, Severity::Deny);
+        assert!(parse_expectations("//~ ERROR complexity").is_err());
+    }
+
+    #[test]
+    fn test_strip_annotations_keeps_offsets() {
+        let source = "a //~ WARN x\r\nb\n";
+        let stripped = strip_annotations(source);
+        assert_eq!(stripped.len(), source.len());
+        assert_eq!(stripped, "a           \r\nb\n");
+    }
+
+    #[test]
+    fn test_fixture_passes() {
+        let report = run_fixture(
+            "fn branchy(x: u8) { //~ WARN complexity\n    if x > 0 {}\n    if x > 1 {}\n}\n\nfn main() {\n    println!(\"hi\"); //~ WARN forbid_word\n}\n",
+        );
+        assert!(report.passed(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_fixture_reports_mismatches() {
+        let report = run_fixture("fn main() { //~ WARN complexity\n    println!(\"hi\");\n}\n");
+        assert_eq!(report.missing[0].to_string(), "line 1: WARN complexity");
+        assert_eq!(report.unexpected[0].to_string(), "line 2: WARN forbid_word");
+    }
+
+    #[test]
+    fn test_fixture_compares_severity() {
+        let report = run_fixture("fn main() {\n    println!(\"hi\"); //~ DENY forbid_word\n}\n");
+        assert_eq!(report.missing[0].to_string(), "line 2: DENY forbid_word");
+        assert_eq!(report.unexpected[0].to_string(), "line 2: WARN forbid_word");
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fixture.rs");
+        fs::write(
+            &path,
+            "fn main() {\n    println!(\"hi\"); //~ DENY forbid_word\n}\n",
+        )
+        .unwrap();
+        let deny = RuleConfig {
+            severity: Severity::Deny,
+            ..forbid_println()
+        };
+        let report = check_fixture(&path, &GrumpinessLevel::Mild, &32, &32, &[deny]).unwrap();
+        assert!(report.passed(), "{:?}", report);
+    }
+}
@@ -0,0 +1,349 @@
+//! Custom rules written in [Rhai](https://rhai.rs), so project specific conventions do not
+//! need a rebuild.
+//!
+//! A script rule is declared in the rules file with either a `script` path (relative to the
+//! rules file) or inline `script_source`:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "few_params"
+//! enabled = true
+//! script_source = '''
+//!     file.functions.filter(|f| f.params > 3).map(|f| #{ line: f.line, message: `${f.name} takes too many parameters` })
+//! '''
+//! ```
+//!
+//! The script sees a read-only constant `file` with the fields
+//! - `items`: `#{ kind, name, line }` for every item (nested into inline modules),
+//! - `functions`: `#{ name, qualified_name, line, end_line, complexity, lines_of_code,
+//!   nesting, returns, params }` as computed by the complexity inspector,
+//! - `comments`: `#{ line, text }` for every line and block comment,
+//!
+//! and a constant `level` holding the grumpiness level (`"mild"`, `"sarcastic"` or `"rude"`).
+//! It returns an array of findings, each either a message string or a `#{ message, line }`
+//! map. Messages are worded by Clippy like those of the other custom rules.
+//!
+//! Scripts are compiled once when the rules file is loaded.
+use std::fs;
+
+use rhai::{AST, Array, Dynamic, Engine, INT, Map, Scope};
+use syn::Item;
+use syn::spanned::Spanned;
+
+use crate::analyzer::complexity_inspector;
+use crate::analyzer::custom_rules::RuleConfig;
+use crate::analyzer::finding::Finding;
+use crate::analyzer::messages::custom_rule;
+use crate::config::GrumpinessLevel;
+
+/// Upper bound of operations per script run, so that a buggy script cannot hang the watcher.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+thread_local! {
+    /// Engine compiling and running all scripts
+    static ENGINE: Engine = {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine
+    };
+}
+
+/// Read-only view of a source file handed to every script rule.
+pub struct SourceView {
+    file: Map,
+}
+
+impl SourceView {
+    pub fn new(source: &str) -> Result<Self, String> {
+        let syntax = syn::parse_file(source).map_err(|e| format!("Syntax error: {}", e))?;
+
+        let mut items = Array::new();
+        collect_items(&syntax.items, "", &mut items);
+
+        let functions = complexity_inspector::analyze_file(&syntax)
+            .into_iter()
+            .map(|m| {
+                let mut function = Map::new();
+                function.insert("name".into(), m.name.into());
+                function.insert("qualified_name".into(), m.qualified_name.into());
+                function.insert("line".into(), (m.start_line as INT).into());
+                function.insert("end_line".into(), (m.end_line as INT).into());
+                function.insert("complexity".into(), (m.cyclomatic_complexity as INT).into());
+                function.insert("lines_of_code".into(), (m.lines_of_code as INT).into());
+                function.insert("nesting".into(), (m.max_nesting_depth as INT).into());
+                function.insert("returns".into(), (m.return_count as INT).into());
+                function.insert("params".into(), (m.param_count as INT).into());
+                Dynamic::from_map(function)
+            })
+            .collect::<Array>();
+
+        let comments = extract_comments(source)
+            .into_iter()
+            .map(|(line, text)| {
+                let mut comment = Map::new();
+                comment.insert("line".into(), (line as INT).into());
+                comment.insert("text".into(), text.into());
+                Dynamic::from_map(comment)
+            })
+            .collect::<Array>();
+
+        let mut file = Map::new();
+        file.insert("items".into(), items.into());
+        file.insert("functions".into(), functions.into());
+        file.insert("comments".into(), comments.into());
+        Ok(SourceView { file })
+    }
+
+    /// Number of entries of the given `file` field.
+    #[cfg(test)]
+    fn len(&self, field: &str) -> usize {
+        self.file[field].clone().into_array().unwrap().len()
+    }
+}
+
+/// Reads and compiles the script of `rule`.
+pub fn compile_script(rule: &RuleConfig) -> Result<AST, String> {
+    let script = match (&rule.script, &rule.script_source) {
+        (_, Some(source)) => source.clone(),
+        (Some(path), None) => fs::read_to_string(path).map_err(|e| {
+            format!(
+                "Failed to read script '{}' of rule '{}': {}",
+                path, rule.name, e
+            )
+        })?,
+        (None, None) => return Err(format!("Rule '{}' has no script", rule.name)),
+    };
+    ENGINE
+        .with(|engine| engine.compile(&script))
+        .map_err(|e| format!("Script of rule '{}' does not compile: {}", rule.name, e))
+}
+
+/// Runs the script of `rule` against `view` and converts its result into findings worded
+/// for `level`. Rules not loaded from a rules file are compiled on the fly.
+pub fn apply_script_rule(
+    rule: &RuleConfig,
+    view: &SourceView,
+    level: &GrumpinessLevel,
+) -> Result<Vec<Finding>, String> {
+    let compiled;
+    let ast = match &rule.compiled_script {
+        Some(ast) => ast,
+        None => {
+            compiled = compile_script(rule)?;
+            &compiled
+        }
+    };
+    let mut scope = Scope::new();
+    scope.push_constant("file", view.file.clone());
+    scope.push_constant("level", level.to_string().to_lowercase());
+
+    let result = ENGINE
+        .with(|engine| engine.eval_ast_with_scope::<Dynamic>(&mut scope, ast))
+        .map_err(|e| format!("Script of rule '{}' failed: {}", rule.name, e))?;
+    if result.is_unit() {
+        return Ok(vec![]);
+    }
+    let results = result.into_array().map_err(|type_name| {
+        format!(
+            "Script of rule '{}' must return an array, but returned {}",
+            rule.name, type_name
+        )
+    })?;
+
+    results
+        .into_iter()
+        .map(|value| to_finding(&rule.name, value, level))
+        .collect()
+}
+
+fn to_finding(rule: &str, value: Dynamic, level: &GrumpinessLevel) -> Result<Finding, String> {
+    if value.is_string() {
+        let message = custom_rule::violation(level, rule, &value.to_string());
+        return Ok(Finding::new(rule, None, message));
+    }
+    let map = value.try_cast::<Map>().ok_or_else(|| {
+        format!(
+            "Script of rule '{}' must return strings or #{{ message, line }} maps",
+            rule
+        )
+    })?;
+    let message = map
+        .get("message")
+        .map(|m| custom_rule::violation(level, rule, &m.to_string()))
+        .ok_or_else(|| format!("Finding of rule '{}' has no message", rule))?;
+    let finding = Finding::new(rule, None, message);
+    match map.get("line").and_then(|line| line.as_int().ok()) {
+        Some(line) if line > 0 => Ok(finding.at_line(line as usize)),
+        _ => Ok(finding),
+    }
+}
+
+fn collect_items(items: &[Item], prefix: &str, out: &mut Array) {
+    for item in items {
+        let (kind, name) = match item {
+            Item::Const(i) => ("const", i.ident.to_string()),
+            Item::Enum(i) => ("enum", i.ident.to_string()),
+            Item::ExternCrate(i) => ("extern_crate", i.ident.to_string()),
+            Item::Fn(i) => ("fn", i.sig.ident.to_string()),
+            Item::Impl(_) => ("impl", String::new()),
+            Item::Macro(i) => (
+                "macro",
+                i.ident.as_ref().map(|i| i.to_string()).unwrap_or_default(),
+            ),
+            Item::Mod(i) => ("mod", i.ident.to_string()),
+            Item::Static(i) => ("static", i.ident.to_string()),
+            Item::Struct(i) => ("struct", i.ident.to_string()),
+            Item::Trait(i) => ("trait", i.ident.to_string()),
+            Item::Type(i) => ("type", i.ident.to_string()),
+            Item::Union(i) => ("union", i.ident.to_string()),
+            Item::Use(_) => ("use", String::new()),
+            _ => ("other", String::new()),
+        };
+        let qualified_name = if prefix.is_empty() || name.is_empty() {
+            name
+        } else {
+            format!("{}::{}", prefix, name)
+        };
+
+        let mut entry = Map::new();
+        entry.insert("kind".into(), kind.into());
+        entry.insert("name".into(), qualified_name.clone().into());
+        entry.insert("line".into(), (item.span().start().line as INT).into());
+        out.push(Dynamic::from_map(entry));
+
+        if let Item::Mod(module) = item
+            && let Some((_, content)) = &module.content
+        {
+            collect_items(content, &qualified_name, out);
+        }
+    }
+}
+
+/// Extracts `//` and `/* */` comments with their (1-based) starting line, skipping string
+/// and char literals.
+fn extract_comments(source: &str) -> Vec<(usize, String)> {
+    let mut comments = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('/', Some('/')) => {
+                let start = i;
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                comments.push((line, chars[start..i].iter().collect()));
+                continue;
+            }
+            ('/', Some('*')) => {
+                let (start, start_line) = (i, line);
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 2).min(chars.len());
+                comments.push((start_line, chars[start..i].iter().collect()));
+                continue;
+            }
+            ('"', _) => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    if chars.get(i) == Some(&'\n') {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+            }
+            ('\'', _) if chars.get(i + 2) == Some(&'\'') => i += 2,
+            ('\'', Some('\\')) => {
+                while i + 1 < chars.len() && chars[i + 1] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            ('\n', _) => line += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    comments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"// header
+mod inner {
+    fn helper(a: u8, b: u8, c: u8, d: u8) {} /* block */
+}
+
+fn main() {
+    let s = "// not a comment";
+}
+"#;
+
+    fn script_rule(source: &str) -> RuleConfig {
+        RuleConfig {
+            name: "scripted".into(),
+            enabled: true,
+            script_source: Some(source.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_source_view() {
+        let view = SourceView::new(SOURCE).unwrap();
+        assert_eq!(view.len("items"), 3);
+        assert_eq!(view.len("functions"), 2);
+        assert_eq!(
+            extract_comments(SOURCE),
+            vec![(1, "// header".to_string()), (3, "/* block */".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_script_returns_findings() {
+        let view = SourceView::new(SOURCE).unwrap();
+        let rule = script_rule(
+            "file.functions.filter(|f| f.params > 3).map(|f| #{ line: f.line, message: `${f.qualified_name} has ${f.params} params` })",
+        );
+        let findings = apply_script_rule(&rule, &view, &GrumpinessLevel::Mild).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("inner::helper has 4 params"));
+        assert_eq!(findings[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_script_sees_level_and_is_compiled_once() {
+        let view = SourceView::new(SOURCE).unwrap();
+        let mut rule = script_rule("[level]");
+        rule.compiled_script = Some(compile_script(&rule).unwrap());
+        rule.script_source = Some("[\"recompiled\"]".into());
+        let findings = apply_script_rule(&rule, &view, &GrumpinessLevel::Rude).unwrap();
+        assert!(
+            findings[0].message.contains("rude"),
+            "{}",
+            findings[0].message
+        );
+    }
+
+    #[test]
+    fn test_script_cannot_modify_view() {
+        let view = SourceView::new(SOURCE).unwrap();
+        let apply = |source| apply_script_rule(&script_rule(source), &view, &GrumpinessLevel::Mild);
+        assert!(apply("file = 1; []").is_err());
+        assert!(apply("42").is_err());
+        assert!(apply("[\"plain message\"]").is_ok());
+    }
+}
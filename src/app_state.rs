@@ -4,16 +4,35 @@
 //! modifying state across multiple threads. `Arc` ensures that the state can be shared
 //! with reference counting, while `RwLock` allows for concurrent read access and
 //! exclusive write access to the state.
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
+use crate::analyzer::finding::Finding;
+
 #[derive(Default)]
 pub struct AppState {
     pub message: String,
+    /// File the current findings were reported for
+    pub path: Option<PathBuf>,
+    /// Findings reported for the last analyzed file
+    pub findings: Vec<Finding>,
+    /// Rules file of the current settings, custom rules are explained from
+    pub custom_rules: String,
+}
+
+impl AppState {
+    /// The first reported finding that offers a fix
+    pub fn fixable_finding(&self) -> Option<&Finding> {
+        self.findings.iter().find(|f| f.is_fixable())
+    }
 }
 
 pub type SharedAppState = Arc<RwLock<AppState>>;
 
-/// Utility to create a new shared app state
-pub fn new_shared_state() -> SharedAppState {
-    Arc::new(RwLock::new(AppState::default()))
+/// Utility to create a new shared app state for the given rules file
+pub fn new_shared_state(custom_rules: String) -> SharedAppState {
+    Arc::new(RwLock::new(AppState {
+        custom_rules,
+        ..AppState::default()
+    }))
 }
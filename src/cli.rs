@@ -18,22 +18,26 @@
 /// * `custom_rules` - Optional path to a user-defined rules file.
 /// * `git_integration` - Flag to enable Git integration for stale commit checks.
 /// * `rules_file` - Optional path to an external rules file.
+/// * `baseline_file` - Optional path to a baseline of known findings that are not reported.
+/// * `message_catalog` - Optional path to a catalog (TOML or JSON) overriding Clippy's messages.
+/// * `locale` - Optional language of Clippy's messages (e.g. `de`).
+/// * `fix` - Flag to apply fixes offered by custom rules automatically.
+/// * `scope` - Optional analysis scope (`file` or `diff`).
+/// * `deny` - Rule ids whose findings block commits in the pre-commit hook.
+/// * `only_mine` - Flag to only report findings on lines last touched by the current git user.
+/// * `adaptive_grumpiness` - Flag to escalate the grumpiness for findings that stay unfixed.
+/// * `offenses_file` - Optional path to the file remembering how long findings stay unfixed.
+/// * `command` - Optional subcommand (e.g. `baseline`) to run instead of the watcher.
 ///
 /// # Example
 ///
 /// ```
-/// let args = CliArgs {
-///     config_file: Some("config.toml".to_string()),
-///     grumpiness_level: Some(GrumpinessLevel::Sarcastic),
-///     verbose: true,
-///     watch_files: vec!["*.rs".to_string()],
-///     ignore_patterns: vec!["target/".to_string()],
-///     max_function_size: Some(50),
-///     max_complexity: Some(10),
-///     custom_rules: Some("custom_rules.toml".to_string()),
-///     git_integration: true,
-///     rules_file: Some("rules.toml".to_string()),
-/// };
+/// let args = CliArgs::from_args(
+///     &["grumpy_clippy"],
+///     &["--grumpiness-level", "sarcastic", "--max-complexity", "10", "-g"],
+/// )
+/// .unwrap();
+/// assert_eq!(args.max_complexity, Some(10));
 /// ```
 ///
 /// ---
@@ -54,47 +58,67 @@
 /// * `custom_rules` - Path to a user-defined rules file.
 /// * `git_integration` - Whether Git integration is enabled.
 /// * `rules_file` - Path to an external rules file.
+/// * `baseline_file` - Path to the baseline file, if findings shall be filtered by a baseline.
+/// * `message_catalog` - Path to a catalog overriding and extending the built-in messages, if any.
+/// * `locale` - Language of Clippy's messages, from the settings, else `LANG`, else English.
+/// * `message_variants` - How one of several variants of a message is picked.
+/// * `message_seed` - Seed for picking message variants randomly, if output shall be reproducible.
+/// * `adaptive_grumpiness` - Whether Clippy gets grumpier about findings that stay unfixed.
+/// * `offenses_file` - Path to the file remembering how long findings stay unfixed, next to the
+///   baseline file unless configured.
+/// * `fix` - Whether fixes offered by custom rules are applied automatically.
+/// * `scope` - Whether findings are reported for the whole file or only for changed lines.
+/// * `deny` - Rule ids whose findings block commits, in addition to custom rules with `severity = "deny"`.
+/// * `config_file` - Path of the configuration file the settings were read from, if any.
+/// * `git` - Settings of the individual git checks (see `GitConfig`).
+/// * `commit_msg` - Conventions commit messages are checked against (see `CommitMsgConfig`).
+/// * `overrides` - Settings for the files matching a path pattern, from `[[overrides]]` sections.
+/// * `rules` - Ids of the rules reported, all if `None`; only restricted by `overrides`.
+/// * `origins` - Where each setting was taken from, by setting key; defaults are missing.
 ///
 /// # Methods
 ///
 /// * `from_sources(cli: CliArgs, file: Option<FileConfig>) -> Self`
 ///   - Merges CLI arguments and configuration file settings into a single configuration.
+/// * `from_layers(cli: CliArgs, layers: &[ConfigLayer]) -> Result<Self, ConfigError>`
+///   - Merges CLI arguments on top of the config layers, recording where each setting came from.
+/// * `with_lang(self, lang: Option<&str>) -> Self`
+///   - Takes the locale from the value of `LANG` (e.g. `de_DE.UTF-8`) unless it was set.
+/// * `settings(&self) -> Vec<(&'static str, Option<toml::Value>)>`
+///   - Lists the effective value of every setting, as it would be written in a config file.
+/// * `for_file(&self, relative_path: &Path) -> MergedConfig`
+///   - Resolves the settings for a file, applying the `overrides` matching it.
 /// * `validate(&self) -> Result<(), ConfigError>`
 ///   - Validates the configuration, ensuring all required fields are set and values are within acceptable ranges.
 ///
 /// # Example
 ///
 /// ```
-/// let cli_args = CliArgs {
-///     grumpiness_level: Some(GrumpinessLevel::Rude),
-///     verbose: true,
-///     watch_files: vec!["*.rs".to_string()],
-///     ignore_patterns: vec!["target/".to_string()],
-///     max_function_size: Some(50),
-///     max_complexity: Some(10),
-///     custom_rules: Some("custom_rules.toml".to_string()),
-///     git_integration: true,
-///     rules_file: Some("rules.toml".to_string()),
-/// };
-///
+/// let cli_args = CliArgs::from_args(&["grumpy_clippy"], &["--grumpiness-level", "rude"]).unwrap();
 /// let file_config = FileConfig {
 ///     grumpiness_level: Some(GrumpinessLevel::Sarcastic),
-///     verbose: Some(false),
-///     watch_files: Some(vec!["*.md".to_string()]),
-///     ignore_patterns: None,
-///     max_function_size: Some(40),
 ///     max_complexity: Some(8),
-///     custom_rules: Some("file_rules.toml".to_string()),
-///     git_integration: Some(false),
-///     rules_file: Some("file_rules.toml".to_string()),
+///     ..Default::default()
 /// };
 ///
 /// let merged_config = MergedConfig::from_sources(cli_args, Some(file_config));
 /// assert_eq!(merged_config.grumpiness_level, GrumpinessLevel::Rude);
+/// assert_eq!(merged_config.max_complexity, 8);
 /// ```
-
-use crate::config::{ConfigError, FileConfig, GrumpinessLevel};
+use crate::analyzer::adaptive::default_offenses_file;
+use crate::analyzer::codeowners::pattern_to_regex;
+use crate::analyzer::git::DiffBase;
+use crate::analyzer::message_catalog::{DEFAULT_LOCALE, locale_from_lang};
+use crate::config::layers::{ConfigLayer, SETTINGS, merge_layers, origin};
+use crate::config::{
+    AnalysisScope, CommitMsgFileConfig, ConfigError, FileConfig, GitFileConfig, GrumpinessLevel,
+    OutputFormat, OverrideFileConfig, VariantSelection,
+};
 use argh::FromArgs;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use toml::Value;
 
 /// CLI to start and configure GrumpyClippy
 #[derive(FromArgs, Debug)]
@@ -104,7 +128,7 @@ pub struct CliArgs {
     #[argh(option)]
     pub config_file: Option<String>,
 
-    /// the level of grumpiness: mild, sarcastic, or rude
+    /// the level of grumpiness: mild, sarcastic, rude or a personality (e.g. pirate)
     #[argh(option)]
     pub grumpiness_level: Option<GrumpinessLevel>,
 
@@ -139,10 +163,208 @@ pub struct CliArgs {
     /// path to external rules file
     #[argh(option)]
     pub rules_file: Option<String>,
+
+    /// path to a baseline file; findings recorded there are not reported
+    #[argh(option)]
+    pub baseline_file: Option<String>,
+
+    /// path to a message catalog (TOML or JSON) overriding Clippy's messages
+    #[argh(option)]
+    pub message_catalog: Option<String>,
+
+    /// language of Clippy's messages, e.g. de or fr (default: from LANG)
+    #[argh(option)]
+    pub locale: Option<String>,
+
+    /// apply fixes offered by custom rules automatically
+    #[argh(switch)]
+    pub fix: bool,
+
+    /// report findings for the whole file or only for lines changed in git: file or diff
+    #[argh(option)]
+    pub scope: Option<AnalysisScope>,
+
+    /// rule ids whose findings block commits in the pre-commit hook (e.g. complexity)
+    #[argh(option)]
+    pub deny: Vec<String>,
+
+    /// only report findings on lines last touched by the current git user (needs
+    /// git_integration)
+    #[argh(switch)]
+    pub only_mine: bool,
+
+    /// get grumpier about findings that stay unfixed save after save
+    #[argh(switch)]
+    pub adaptive_grumpiness: bool,
+
+    /// path to the file remembering unfixed findings for adaptive grumpiness (default: next to
+    /// the baseline file)
+    #[argh(option)]
+    pub offenses_file: Option<String>,
+
+    #[argh(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl CliArgs {
+    /// Keys of the settings given on the command line
+    fn given_settings(&self) -> Vec<&'static str> {
+        [
+            ("grumpiness_level", self.grumpiness_level.is_some()),
+            ("verbose", self.verbose),
+            ("watch_files", !self.watch_files.is_empty()),
+            ("ignore_patterns", !self.ignore_patterns.is_empty()),
+            ("max_function_size", self.max_function_size.is_some()),
+            ("max_complexity", self.max_complexity.is_some()),
+            ("custom_rules", self.custom_rules.is_some()),
+            ("git_integration", self.git_integration),
+            ("rules_file", self.rules_file.is_some()),
+            ("baseline_file", self.baseline_file.is_some()),
+            ("message_catalog", self.message_catalog.is_some()),
+            ("locale", self.locale.is_some()),
+            ("adaptive_grumpiness", self.adaptive_grumpiness),
+            ("offenses_file", self.offenses_file.is_some()),
+            ("fix", self.fix),
+            ("scope", self.scope.is_some()),
+            ("deny", !self.deny.is_empty()),
+            ("git.only_mine", self.only_mine),
+        ]
+        .into_iter()
+        .filter_map(|(key, given)| given.then_some(key))
+        .collect()
+    }
+}
+
+/// Subcommands that run once instead of starting the watcher and GUI
+#[derive(FromArgs, Debug)]
+#[argh(subcommand)]
+pub enum Command {
+    Baseline(BaselineArgs),
+    TestRules(TestRulesArgs),
+    InstallHook(InstallHookArgs),
+    Hook(HookArgs),
+    Hotspots(HotspotsArgs),
+    Ownership(OwnershipArgs),
+    Explain(ExplainArgs),
+    Config(ConfigArgs),
+}
+
+/// Record the current findings so that only new ones are reported afterwards
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "baseline")]
+pub struct BaselineArgs {
+    /// file to write the baseline to (default: the configured baseline file)
+    #[argh(option, short = 'o')]
+    pub output: Option<String>,
+
+    /// files or directories to analyze (default: src)
+    #[argh(positional)]
+    pub paths: Vec<String>,
+}
+
+/// Check custom rules against `.rs` fixtures annotated with `//~ WARN|DENY <rule_id>`
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "test-rules")]
+pub struct TestRulesArgs {
+    /// rules file to test (default: the configured custom rules)
+    #[argh(option)]
+    pub rules: Option<String>,
+
+    /// directory (or single file) containing the fixtures
+    #[argh(positional)]
+    pub dir: String,
+}
+
+/// Install a git pre-commit hook that runs `grumpy_clippy hook`
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "install-hook")]
+pub struct InstallHookArgs {
+    /// overwrite an existing pre-commit hook
+    #[argh(switch)]
+    pub force: bool,
+}
+
+/// Check the staged files and block the commit on `deny` findings
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "hook")]
+pub struct HookArgs {
+    /// check the commit message in the given file instead of the staged files
+    #[argh(option)]
+    pub commit_msg: Option<String>,
+}
+
+/// Rank files or functions by commits in the last days times their complexity
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "hotspots")]
+pub struct HotspotsArgs {
+    /// number of days of history to take into account (default: 90)
+    #[argh(option, default = "90")]
+    pub days: u64,
+
+    /// maximum number of hotspots to show (default: 20)
+    #[argh(option, default = "20")]
+    pub limit: usize,
+
+    /// rank whole files instead of functions
+    #[argh(switch)]
+    pub files: bool,
+
+    /// output format: text or json (default: text)
+    #[argh(option, default = "OutputFormat::Text")]
+    pub format: OutputFormat,
+}
+
+/// Report authors, top author share and bus factor per directory
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "ownership")]
+pub struct OwnershipArgs {
+    /// aggregate directories to at most this many path components (e.g. 1 for `src`)
+    #[argh(option)]
+    pub depth: Option<usize>,
+
+    /// months without commits after which an author counts as inactive (default: 6)
+    #[argh(option, default = "6")]
+    pub inactive_months: u64,
+
+    /// output format: text or json (default: text)
+    #[argh(option, default = "OutputFormat::Text")]
+    pub format: OutputFormat,
+
+    /// files or directories to report on (default: the whole repository)
+    #[argh(positional)]
+    pub paths: Vec<String>,
+}
+
+/// Explain why a rule (built-in, custom or clippy lint) complains and how to fix it
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "explain")]
+pub struct ExplainArgs {
+    /// id of the rule, e.g. `complexity`, `no_unwrap` or `clippy::needless_return`
+    #[argh(positional)]
+    pub rule_id: String,
+}
+
+/// Inspect the configuration
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "config")]
+pub struct ConfigArgs {
+    #[argh(subcommand)]
+    pub command: ConfigCommand,
 }
 
+#[derive(FromArgs, Debug)]
+#[argh(subcommand)]
+pub enum ConfigCommand {
+    Show(ConfigShowArgs),
+}
+
+/// Print the effective settings and where each of them came from
+#[derive(FromArgs, Debug)]
+#[argh(subcommand, name = "show")]
+pub struct ConfigShowArgs {}
+
 /// Final merged config: cli args >> config file
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MergedConfig {
     pub grumpiness_level: GrumpinessLevel,
     pub verbose: bool,
@@ -153,10 +375,124 @@ pub struct MergedConfig {
     pub custom_rules: String,
     pub git_integration: bool,
     pub rules_file: String,
+    pub baseline_file: Option<String>,
+    pub message_catalog: Option<String>,
+    pub locale: String,
+    pub message_variants: VariantSelection,
+    pub message_seed: Option<u64>,
+    pub adaptive_grumpiness: bool,
+    pub offenses_file: String,
+    pub fix: bool,
+    pub scope: AnalysisScope,
+    pub deny: Vec<String>,
+    pub config_file: Option<String>,
+    pub git: GitConfig,
+    pub commit_msg: CommitMsgConfig,
+    pub overrides: Vec<FileOverride>,
+    pub rules: Option<Vec<String>>,
+    pub origins: BTreeMap<String, String>,
+}
+
+/// An `[[overrides]]` section with its path pattern compiled once
+#[derive(Debug, Clone)]
+pub struct FileOverride {
+    pub settings: OverrideFileConfig,
+    /// Regex of the path pattern, or why the pattern is invalid (see `MergedConfig::validate`)
+    pattern: Result<Regex, String>,
+}
+
+impl FileOverride {
+    fn new(settings: OverrideFileConfig) -> Self {
+        let pattern = Regex::new(&pattern_to_regex(&settings.path)).map_err(|e| e.to_string());
+        FileOverride { settings, pattern }
+    }
+
+    /// Whether the section applies to the file at `path` (relative, with `/` separators).
+    fn matches(&self, path: &str) -> bool {
+        self.pattern
+            .as_ref()
+            .is_ok_and(|pattern| pattern.is_match(path))
+    }
+}
+
+/// Settings of the git checks, taken from the `[git]` section of the config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitConfig {
+    /// Days without commits after which a file is reported as stale
+    pub stale_days: u64,
+    /// Whether stale files are reported
+    pub check_stale: bool,
+    /// Whether the most frequent author of a file is reported
+    pub report_author: bool,
+    /// What the changed lines of `scope = "diff"` are computed against
+    pub diff_base: DiffBase,
+    /// Whether findings are annotated with who last touched them
+    pub blame_findings: bool,
+    /// Whether findings of other authors than the current git user are hidden
+    pub only_mine: bool,
+    /// Whether `CODEOWNERS` is consulted for the owners of a file
+    pub codeowners: bool,
+}
+
+impl GitConfig {
+    fn from_file(file: Option<&GitFileConfig>) -> Self {
+        let file = file.cloned().unwrap_or_default();
+        GitConfig {
+            stale_days: file.stale_days.unwrap_or(7),
+            check_stale: file.check_stale.unwrap_or(true),
+            report_author: file.report_author.unwrap_or(true),
+            diff_base: file
+                .diff_base
+                .as_deref()
+                .map(DiffBase::from)
+                .unwrap_or(DiffBase::Head),
+            blame_findings: file.blame_findings.unwrap_or(true),
+            only_mine: file.only_mine.unwrap_or(false),
+            codeowners: file.codeowners.unwrap_or(true),
+        }
+    }
+}
+
+/// Commit message conventions, taken from the `[commit_msg]` section of the config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitMsgConfig {
+    /// Allowed conventional commit types (`feat(scope)!: subject`); empty disables the check
+    pub types: Vec<String>,
+    pub max_subject_length: usize,
+    pub max_body_line_length: usize,
+    /// Regex of the issue key every message has to reference, if any
+    pub issue_key: Option<String>,
+}
+
+impl CommitMsgConfig {
+    fn from_file(file: Option<&CommitMsgFileConfig>) -> Self {
+        let file = file.cloned().unwrap_or_default();
+        CommitMsgConfig {
+            types: file.types.unwrap_or_else(|| {
+                [
+                    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci",
+                    "chore", "revert",
+                ]
+                .map(String::from)
+                .to_vec()
+            }),
+            max_subject_length: file.max_subject_length.unwrap_or(72),
+            max_body_line_length: file.max_body_line_length.unwrap_or(72),
+            issue_key: file.issue_key,
+        }
+    }
 }
 
 impl MergedConfig {
     pub fn from_sources(cli: CliArgs, file: Option<FileConfig>) -> Self {
+        let baseline_file = cli
+            .baseline_file
+            .or_else(|| file.as_ref().and_then(|f| f.baseline_file.clone()));
+        let offenses_file = cli
+            .offenses_file
+            .or_else(|| file.as_ref().and_then(|f| f.offenses_file.clone()))
+            .unwrap_or_else(|| default_offenses_file(baseline_file.as_deref()));
+
         MergedConfig {
             grumpiness_level: cli
                 .grumpiness_level
@@ -195,15 +531,239 @@ impl MergedConfig {
                 .unwrap_or("rules.toml".into()),
 
             git_integration: cli.git_integration
-                || file.as_ref().and_then(|f| f.git_integration).unwrap_or(false),
+                || file
+                    .as_ref()
+                    .and_then(|f| f.git_integration)
+                    .unwrap_or(false),
 
             rules_file: cli
                 .rules_file
                 .or_else(|| file.as_ref().and_then(|f| f.rules_file.clone()))
                 .unwrap_or_else(|| "my_custom_rules.toml".into()),
+
+            baseline_file,
+
+            message_catalog: cli
+                .message_catalog
+                .or_else(|| file.as_ref().and_then(|f| f.message_catalog.clone())),
+
+            locale: cli
+                .locale
+                .or_else(|| file.as_ref().and_then(|f| f.locale.clone()))
+                .map(|locale| locale.to_lowercase())
+                .unwrap_or_else(|| DEFAULT_LOCALE.to_string()),
+
+            message_variants: file
+                .as_ref()
+                .and_then(|f| f.message_variants)
+                .unwrap_or_default(),
+
+            message_seed: file.as_ref().and_then(|f| f.message_seed),
+
+            adaptive_grumpiness: cli.adaptive_grumpiness
+                || file
+                    .as_ref()
+                    .and_then(|f| f.adaptive_grumpiness)
+                    .unwrap_or(false),
+
+            offenses_file,
+
+            fix: cli.fix || file.as_ref().and_then(|f| f.fix).unwrap_or(false),
+
+            scope: cli
+                .scope
+                .or_else(|| file.as_ref().and_then(|f| f.scope.clone()))
+                .unwrap_or_default(),
+
+            deny: if !cli.deny.is_empty() {
+                cli.deny.clone()
+            } else {
+                file.as_ref()
+                    .and_then(|f| f.deny.clone())
+                    .unwrap_or_default()
+            },
+
+            config_file: cli.config_file.clone(),
+
+            git: GitConfig {
+                only_mine: cli.only_mine
+                    || file
+                        .as_ref()
+                        .and_then(|f| f.git.as_ref())
+                        .and_then(|g| g.only_mine)
+                        .unwrap_or(false),
+                ..GitConfig::from_file(file.as_ref().and_then(|f| f.git.as_ref()))
+            },
+
+            commit_msg: CommitMsgConfig::from_file(
+                file.as_ref().and_then(|f| f.commit_msg.as_ref()),
+            ),
+
+            overrides: file
+                .as_ref()
+                .and_then(|f| f.overrides.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .map(FileOverride::new)
+                .collect(),
+
+            rules: None,
+
+            origins: BTreeMap::new(),
         }
     }
 
+    pub fn from_layers(cli: CliArgs, layers: &[ConfigLayer]) -> Result<Self, ConfigError> {
+        let given = cli.given_settings();
+        let file = match layers.is_empty() {
+            true => None,
+            false => Some(merge_layers(layers)?),
+        };
+        let mut config = Self::from_sources(cli, file);
+        config.origins = SETTINGS
+            .iter()
+            .filter_map(|&key| {
+                let origin = match given.contains(&key) {
+                    true => Some("command line".to_string()),
+                    false => origin(layers, key),
+                };
+                origin.map(|origin| (key.to_string(), origin))
+            })
+            .collect();
+        Ok(config)
+    }
+
+    pub fn with_lang(mut self, lang: Option<&str>) -> Self {
+        let locale = lang
+            .filter(|_| !self.origins.contains_key("locale"))
+            .and_then(locale_from_lang);
+        if let Some(locale) = locale {
+            self.locale = locale;
+            self.origins.insert("locale".into(), "LANG".into());
+        }
+        self
+    }
+
+    pub fn settings(&self) -> Vec<(&'static str, Option<Value>)> {
+        let string = |value: &str| Some(Value::String(value.to_string()));
+        let strings = |values: &[String]| {
+            Some(Value::Array(
+                values.iter().cloned().map(Value::String).collect(),
+            ))
+        };
+        let int = |value: i64| Some(Value::Integer(value));
+        let bool = |value: bool| Some(Value::Boolean(value));
+        let git = &self.git;
+        let commit_msg = &self.commit_msg;
+        vec![
+            (
+                "grumpiness_level",
+                string(&self.grumpiness_level.to_string().to_lowercase()),
+            ),
+            ("verbose", bool(self.verbose)),
+            ("watch_files", strings(&self.watch_files)),
+            ("ignore_patterns", strings(&self.ignore_patterns)),
+            ("max_function_size", int(self.max_function_size.into())),
+            ("max_complexity", int(self.max_complexity.into())),
+            ("custom_rules", string(&self.custom_rules)),
+            ("git_integration", bool(self.git_integration)),
+            ("rules_file", string(&self.rules_file)),
+            (
+                "baseline_file",
+                self.baseline_file.as_deref().and_then(string),
+            ),
+            (
+                "message_catalog",
+                self.message_catalog.as_deref().and_then(string),
+            ),
+            ("locale", string(&self.locale)),
+            (
+                "message_variants",
+                string(match self.message_variants {
+                    VariantSelection::Random => "random",
+                    VariantSelection::RoundRobin => "round_robin",
+                }),
+            ),
+            (
+                "message_seed",
+                // TOML integers are signed, larger seeds are shown as string
+                self.message_seed.map(|seed| match i64::try_from(seed) {
+                    Ok(seed) => Value::Integer(seed),
+                    Err(_) => Value::String(seed.to_string()),
+                }),
+            ),
+            ("adaptive_grumpiness", bool(self.adaptive_grumpiness)),
+            ("offenses_file", string(&self.offenses_file)),
+            ("fix", bool(self.fix)),
+            ("scope", string(&self.scope.to_string().to_lowercase())),
+            ("deny", strings(&self.deny)),
+            ("git.stale_days", int(git.stale_days as i64)),
+            ("git.check_stale", bool(git.check_stale)),
+            ("git.report_author", bool(git.report_author)),
+            (
+                "git.diff_base",
+                string(match &git.diff_base {
+                    DiffBase::Index => "index",
+                    DiffBase::Head => "HEAD",
+                    DiffBase::Branch(branch) => branch,
+                }),
+            ),
+            ("git.blame_findings", bool(git.blame_findings)),
+            ("git.only_mine", bool(git.only_mine)),
+            ("git.codeowners", bool(git.codeowners)),
+            ("commit_msg.types", strings(&commit_msg.types)),
+            (
+                "commit_msg.max_subject_length",
+                int(commit_msg.max_subject_length as i64),
+            ),
+            (
+                "commit_msg.max_body_line_length",
+                int(commit_msg.max_body_line_length as i64),
+            ),
+            (
+                "commit_msg.issue_key",
+                commit_msg.issue_key.as_deref().and_then(string),
+            ),
+            (
+                "overrides",
+                Some(Value::Array(
+                    self.overrides
+                        .iter()
+                        .map(|file_override| override_value(&file_override.settings))
+                        .collect(),
+                )),
+            ),
+        ]
+    }
+
+    pub fn for_file(&self, relative_path: &Path) -> MergedConfig {
+        let path = relative_path.to_string_lossy().replace('\\', "/");
+        let mut config = self.clone();
+        for file_override in self.overrides.iter().filter(|o| o.matches(&path)) {
+            let file_override = &file_override.settings;
+            if let Some(level) = &file_override.grumpiness_level {
+                config.grumpiness_level = level.clone();
+            }
+            if let Some(max_function_size) = file_override.max_function_size {
+                config.max_function_size = max_function_size;
+            }
+            if let Some(max_complexity) = file_override.max_complexity {
+                config.max_complexity = max_complexity;
+            }
+            if let Some(rules) = &file_override.rules {
+                config.rules = Some(rules.clone());
+            }
+        }
+        config
+    }
+
+    /// Whether findings of `rule` are reported with these settings.
+    pub fn reports(&self, rule: &str) -> bool {
+        self.rules
+            .as_ref()
+            .is_none_or(|rules| rules.iter().any(|r| r == rule))
+    }
+
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.max_function_size == 0 {
             return Err(ConfigError::ValueTooSmall(
@@ -222,14 +782,74 @@ impl MergedConfig {
         if self.watch_files.is_empty() {
             return Err(ConfigError::MissingWatchFiles);
         }
+        for FileOverride {
+            settings: file_override,
+            pattern,
+        } in &self.overrides
+        {
+            if let Err(e) = pattern {
+                return Err(ConfigError::InvalidPattern(
+                    format!("overrides.path '{}'", file_override.path),
+                    e.clone(),
+                ));
+            }
+            for (param, value) in [
+                (
+                    "overrides.max_function_size",
+                    file_override.max_function_size,
+                ),
+                ("overrides.max_complexity", file_override.max_complexity),
+            ] {
+                if value == Some(0) {
+                    return Err(ConfigError::ValueTooSmall(param.to_owned(), 0, 0));
+                }
+            }
+        }
+        if let Some(issue_key) = &self.commit_msg.issue_key
+            && let Err(e) = regex::Regex::new(issue_key)
+        {
+            return Err(ConfigError::InvalidPattern(
+                "commit_msg.issue_key".to_owned(),
+                e.to_string(),
+            ));
+        }
 
         Ok(())
     }
 }
 
+/// An `[[overrides]]` section as inline table, with the settings it sets.
+fn override_value(file_override: &OverrideFileConfig) -> Value {
+    let mut table = toml::Table::new();
+    table.insert("path".into(), Value::String(file_override.path.clone()));
+    if let Some(level) = &file_override.grumpiness_level {
+        let level = level.to_string().to_lowercase();
+        table.insert("grumpiness_level".into(), Value::String(level));
+    }
+    if let Some(max_function_size) = file_override.max_function_size {
+        table.insert(
+            "max_function_size".into(),
+            Value::Integer(max_function_size.into()),
+        );
+    }
+    if let Some(max_complexity) = file_override.max_complexity {
+        table.insert(
+            "max_complexity".into(),
+            Value::Integer(max_complexity.into()),
+        );
+    }
+    if let Some(rules) = &file_override.rules {
+        let rules = rules.iter().cloned().map(Value::String).collect();
+        table.insert("rules".into(), Value::Array(rules));
+    }
+    Value::Table(table)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyzer::adaptive::DEFAULT_OFFENSES_FILE;
+    use crate::config::layers::LayerSource;
     use argh::FromArgs;
 
     fn parse_args(args: &[&str]) -> CliArgs {
@@ -273,20 +893,261 @@ mod tests {
         let args = parse_args(&["--output-format", "txt", "--max-complexity", "8"]);
         let file_config = FileConfig {
             grumpiness_level: Some(GrumpinessLevel::Sarcastic),
-            verbose: Some(false),
-            watch_files: Some(vec!["*.md".into()]),
-            ignore_patterns: None,
-            max_function_size: Some(50),
             max_complexity: Some(5),
-            custom_rules: Some("custom.toml".into()),
-            git_integration: Some(false),
-            rules_file: Some("rules.toml".into()),
+            ..Default::default()
         };
         let config = MergedConfig::from_sources(args, Some(file_config));
         assert_eq!(config.grumpiness_level, GrumpinessLevel::Sarcastic);
         assert_eq!(config.max_complexity, 8)
     }
 
+    #[test]
+    fn test_git_settings() {
+        let config = MergedConfig::from_sources(parse_args(&[]), None);
+        assert_eq!(
+            config.git,
+            GitConfig {
+                stale_days: 7,
+                check_stale: true,
+                report_author: true,
+                diff_base: DiffBase::Head,
+                blame_findings: true,
+                only_mine: false,
+                codeowners: true,
+            }
+        );
+
+        let file_config: FileConfig =
+            toml::from_str("[git]\nstale_days = 30\ncheck_stale = false\n").unwrap();
+        let config = MergedConfig::from_sources(parse_args(&["-g"]), Some(file_config));
+        assert!(config.git_integration);
+        assert_eq!(config.git.stale_days, 30);
+        assert!(!config.git.check_stale);
+        assert!(config.git.report_author);
+
+        let config = MergedConfig::from_sources(parse_args(&["--only-mine"]), None);
+        assert!(config.git.only_mine);
+    }
+
+    #[test]
+    fn test_scope_settings() {
+        let config = MergedConfig::from_sources(parse_args(&[]), None);
+        assert_eq!(config.scope, AnalysisScope::File);
+
+        let file_config: FileConfig =
+            toml::from_str("scope = \"file\"\n[git]\ndiff_base = \"index\"\n").unwrap();
+        let config =
+            MergedConfig::from_sources(parse_args(&["--scope", "diff"]), Some(file_config));
+        assert_eq!(config.scope, AnalysisScope::Diff);
+        assert_eq!(config.git.diff_base, DiffBase::Index);
+    }
+
+    #[test]
+    fn test_baseline_subcommand() {
+        let args = parse_args(&["--baseline-file", "known.json", "baseline", "src/analyzer"]);
+        match &args.command {
+            Some(Command::Baseline(baseline)) => {
+                assert_eq!(baseline.paths, vec!["src/analyzer"]);
+                assert!(baseline.output.is_none());
+            }
+            _ => panic!("Expected baseline subcommand"),
+        }
+        let config = MergedConfig::from_sources(args, None);
+        assert_eq!(config.baseline_file.as_deref(), Some("known.json"));
+    }
+
+    #[test]
+    fn test_offenses_file_defaults_next_to_baseline() {
+        let config = MergedConfig::from_sources(parse_args(&[]), None);
+        assert_eq!(config.offenses_file, DEFAULT_OFFENSES_FILE);
+
+        let file_config: FileConfig =
+            toml::from_str("baseline_file = \"ci/known.json\"\n").unwrap();
+        let config = MergedConfig::from_sources(parse_args(&[]), Some(file_config.clone()));
+        assert_eq!(
+            Path::new(&config.offenses_file),
+            Path::new("ci").join(DEFAULT_OFFENSES_FILE)
+        );
+
+        let args = parse_args(&["--offenses-file", "offenses.json"]);
+        let config = MergedConfig::from_sources(args, Some(file_config));
+        assert_eq!(config.offenses_file, "offenses.json");
+    }
+
+    #[test]
+    fn test_test_rules_subcommand() {
+        let args = parse_args(&["test-rules", "--rules", "mine.toml", "tests/rules"]);
+        match args.command {
+            Some(Command::TestRules(test_rules)) => {
+                assert_eq!(test_rules.dir, "tests/rules");
+                assert_eq!(test_rules.rules.as_deref(), Some("mine.toml"));
+            }
+            _ => panic!("Expected test-rules subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_hook_subcommands() {
+        let args = parse_args(&["--deny", "complexity", "install-hook", "--force"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::InstallHook(InstallHookArgs { force: true }))
+        ));
+        let config = MergedConfig::from_sources(args, None);
+        assert_eq!(config.deny, vec!["complexity"]);
+
+        let args = parse_args(&["hook", "--commit-msg", ".git/COMMIT_EDITMSG"]);
+        match args.command {
+            Some(Command::Hook(hook)) => {
+                assert_eq!(hook.commit_msg.as_deref(), Some(".git/COMMIT_EDITMSG"))
+            }
+            _ => panic!("Expected hook subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_hotspots_subcommand() {
+        match parse_args(&["hotspots", "--days", "30", "--format", "json"]).command {
+            Some(Command::Hotspots(hotspots)) => {
+                assert_eq!(hotspots.days, 30);
+                assert_eq!(hotspots.limit, 20);
+                assert!(!hotspots.files);
+                assert_eq!(hotspots.format, OutputFormat::Json);
+            }
+            _ => panic!("Expected hotspots subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_ownership_subcommand() {
+        match parse_args(&["ownership", "--depth", "2", "src"]).command {
+            Some(Command::Ownership(ownership)) => {
+                assert_eq!(ownership.depth, Some(2));
+                assert_eq!(ownership.inactive_months, 6);
+                assert_eq!(ownership.paths, vec!["src"]);
+            }
+            _ => panic!("Expected ownership subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_explain_subcommand() {
+        match parse_args(&["explain", "clippy::needless_return"]).command {
+            Some(Command::Explain(explain)) => {
+                assert_eq!(explain.rule_id, "clippy::needless_return")
+            }
+            _ => panic!("Expected explain subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_config_show_subcommand_and_origins() {
+        assert!(matches!(
+            parse_args(&["config", "show"]).command,
+            Some(Command::Config(ConfigArgs {
+                command: ConfigCommand::Show(_)
+            }))
+        ));
+
+        let layers = [ConfigLayer {
+            source: LayerSource::ConfigFile(".grumpyclippy.toml".into()),
+            table: "max_complexity = 8\nverbose = true\n[git]\nstale_days = 3\n"
+                .parse()
+                .unwrap(),
+        }];
+        let config =
+            MergedConfig::from_layers(parse_args(&["--verbose", "--locale", "fr"]), &layers)
+                .unwrap();
+        assert_eq!(config.max_complexity, 8);
+        assert_eq!(config.origins["max_complexity"], "'.grumpyclippy.toml'");
+        assert_eq!(config.origins["git.stale_days"], "'.grumpyclippy.toml'");
+        assert_eq!(config.origins["verbose"], "command line");
+        assert_eq!(config.origins["locale"], "command line");
+        assert!(!config.origins.contains_key("max_function_size"));
+        assert_eq!(config.clone().with_lang(Some("de_DE.UTF-8")).locale, "fr");
+        let config_from_lang = MergedConfig::from_layers(parse_args(&[]), &layers)
+            .unwrap()
+            .with_lang(Some("de_DE.UTF-8"));
+        assert_eq!(config_from_lang.locale, "de");
+        assert_eq!(config_from_lang.origins["locale"], "LANG");
+
+        let settings = config.settings();
+        assert_eq!(
+            settings.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            SETTINGS
+        );
+        assert!(settings.contains(&("git.stale_days", Some(Value::Integer(3)))));
+        assert!(settings.contains(&("baseline_file", None)));
+        let seeded = MergedConfig {
+            message_seed: Some(u64::MAX),
+            ..config
+        };
+        assert!(
+            seeded
+                .settings()
+                .contains(&("message_seed", Some(Value::String(u64::MAX.to_string()))))
+        );
+    }
+
+    #[test]
+    fn test_overrides_per_file() {
+        let file_config: FileConfig = toml::from_str(
+            r#"
+            max_complexity = 10
+            [[overrides]]
+            path = "tests/"
+            max_complexity = 40
+            grumpiness_level = "mild"
+            [[overrides]]
+            path = "src/**/generated_*.rs"
+            max_function_size = 200
+            rules = ["complexity"]
+        "#,
+        )
+        .unwrap();
+        let config = MergedConfig::from_sources(
+            parse_args(&["--grumpiness-level", "rude"]),
+            Some(file_config),
+        );
+        assert!(config.validate().is_ok());
+
+        let tests = config.for_file(Path::new("tests/cli/args.rs"));
+        assert_eq!(tests.max_complexity, 40);
+        assert_eq!(tests.grumpiness_level, GrumpinessLevel::Mild);
+        assert!(tests.reports("no_todo_comments"));
+
+        let generated = config.for_file(Path::new("src/model/generated_api.rs"));
+        assert_eq!(generated.max_complexity, 10);
+        assert_eq!(generated.max_function_size, 200);
+        assert_eq!(generated.grumpiness_level, GrumpinessLevel::Rude);
+        assert!(generated.reports("complexity"));
+        assert!(!generated.reports("no_todo_comments"));
+
+        let other = config.for_file(Path::new("src/tests.rs"));
+        assert_eq!(other.max_complexity, 10);
+        assert!(other.reports("no_todo_comments"));
+
+        let file_config: FileConfig =
+            toml::from_str("[[overrides]]\npath = \"tests/\"\nmax_complexity = 0\n").unwrap();
+        let config = MergedConfig::from_sources(parse_args(&[]), Some(file_config));
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ValueTooSmall(_, _, _))
+        ));
+    }
+
+    #[test]
+    fn test_validation_error_invalid_issue_key() {
+        let file_config: FileConfig =
+            toml::from_str("[commit_msg]\nissue_key = \"[A-Z\"\n").unwrap();
+        let config = MergedConfig::from_sources(parse_args(&[]), Some(file_config));
+        assert_eq!(config.commit_msg.max_subject_length, 72);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidPattern(_, _))
+        ))
+    }
+
     #[test]
     fn test_validation_error_max_function_size() {
         let args = parse_args(&["--max-function-size", "0"]);
@@ -311,15 +1172,8 @@ mod tests {
     fn test_validation_error_empty_watch_files() {
         let args = parse_args(&[]);
         let file_config = FileConfig {
-            grumpiness_level: Some(GrumpinessLevel::Sarcastic),
-            verbose: Some(false),
             watch_files: Some(vec![]),
-            ignore_patterns: None,
-            max_function_size: Some(50),
-            max_complexity: Some(5),
-            custom_rules: Some("custom.toml".into()),
-            git_integration: Some(false),
-            rules_file: Some("rules.toml".into()),
+            ..Default::default()
         };
         let config = MergedConfig::from_sources(args, Some(file_config));
         assert!(matches!(
@@ -0,0 +1,430 @@
+//! One-shot subcommands that run instead of the watcher and the GUI.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::actions::{
+    analyze_file_complexity, analyze_file_with_custom_rules, analyze_source_complexity,
+    baseline_path, workdir_path,
+};
+use crate::analyzer::baseline::{Baseline, DEFAULT_BASELINE_FILE};
+use crate::analyzer::commit_message::lint_commit_message;
+use crate::analyzer::custom_rules::{apply_rules_by, load_custom_rules_from_toml};
+use crate::analyzer::explain::explain;
+use crate::analyzer::finding::Severity;
+use crate::analyzer::git::GitInspector;
+use crate::analyzer::hotspots::find_hotspots;
+use crate::analyzer::messages::{commit_message, pre_commit};
+use crate::analyzer::ownership::ownership_report;
+use crate::analyzer::rule_harness::check_fixture;
+use crate::cli::{
+    BaselineArgs, Command, ConfigArgs, ConfigCommand, ExplainArgs, HookArgs, HotspotsArgs,
+    InstallHookArgs, MergedConfig, OwnershipArgs, TestRulesArgs,
+};
+use crate::config::OutputFormat;
+use crate::watcher::shall_be_ignored;
+
+/// Marks pre-commit hooks written by `install-hook`, so they may be replaced safely.
+const HOOK_MARKER: &str = "# Installed by grumpy_clippy install-hook";
+
+/// Runs the given subcommand with the merged configuration.
+pub fn run(command: Command, config: &MergedConfig) -> Result<(), String> {
+    match command {
+        Command::Baseline(args) => create_baseline(&args, config),
+        Command::TestRules(args) => test_rules(&args, config),
+        Command::InstallHook(args) => install_hook(&args, config),
+        Command::Hook(HookArgs {
+            commit_msg: Some(path),
+        }) => check_commit_message(Path::new(&path), config),
+        Command::Hook(_) => run_hook(config),
+        Command::Hotspots(args) => show_hotspots(&args),
+        Command::Ownership(args) => show_ownership(&args),
+        Command::Explain(args) => show_explanation(&args, config),
+        Command::Config(ConfigArgs {
+            command: ConfigCommand::Show(_),
+        }) => show_config(config),
+    }
+}
+
+/// Analyzes all Rust files below the given paths and records their findings in a baseline.
+fn create_baseline(args: &BaselineArgs, config: &MergedConfig) -> Result<(), String> {
+    let output = args
+        .output
+        .clone()
+        .or_else(|| config.baseline_file.clone())
+        .unwrap_or_else(|| DEFAULT_BASELINE_FILE.into());
+    let roots = if args.paths.is_empty() {
+        vec!["src".to_string()]
+    } else {
+        args.paths.clone()
+    };
+
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_rust_files(Path::new(root), &config.ignore_patterns, &mut files)?;
+    }
+
+    let rules = load_custom_rules_from_toml(&config.custom_rules)?.unwrap_or_default();
+    let mut baseline = Baseline::default();
+    for file in &files {
+        let relative_path = baseline_path(file);
+        let config = &config.for_file(&workdir_path(file));
+        match analyze_file_complexity(
+            file,
+            &config.grumpiness_level,
+            &config.max_function_size,
+            &config.max_complexity,
+        ) {
+            Ok((_, findings)) => findings
+                .iter()
+                .filter(|finding| config.reports(&finding.rule))
+                .for_each(|finding| baseline.insert(&relative_path, finding)),
+            Err(e) => eprintln!("⚠️ Skipping '{}': {}", file.display(), e),
+        }
+        match analyze_file_with_custom_rules(file, &rules, &|_| config.grumpiness_level.clone()) {
+            Ok((_, findings)) => findings
+                .iter()
+                .filter(|finding| config.reports(&finding.rule))
+                .for_each(|finding| baseline.insert(&relative_path, finding)),
+            Err(e) => eprintln!("⚠️ Skipping custom rules for '{}': {}", file.display(), e),
+        }
+    }
+
+    baseline
+        .save(Path::new(&output))
+        .map_err(|e| e.to_string())?;
+    println!(
+        "📝 Recorded {} finding(s) from {} file(s) in '{}'",
+        baseline.len(),
+        files.len(),
+        output
+    );
+    Ok(())
+}
+
+/// Runs every fixture below the given directory and reports mismatching findings.
+fn test_rules(args: &TestRulesArgs, config: &MergedConfig) -> Result<(), String> {
+    let rules = match &args.rules {
+        Some(rules_path) => load_custom_rules_from_toml(rules_path)?
+            .ok_or_else(|| format!("Rules file '{}' does not exist", rules_path))?,
+        None => load_custom_rules_from_toml(&config.custom_rules)?.unwrap_or_default(),
+    };
+
+    let mut fixtures = Vec::new();
+    collect_rust_files(Path::new(&args.dir), &[], &mut fixtures)?;
+    if fixtures.is_empty() {
+        return Err(format!("No fixtures found in '{}'", args.dir));
+    }
+
+    let mut failed = 0;
+    for fixture in &fixtures {
+        let report = check_fixture(
+            fixture,
+            &config.grumpiness_level,
+            &config.max_function_size,
+            &config.max_complexity,
+            &rules,
+        )?;
+        if report.passed() {
+            println!("✅ {}", fixture.display());
+            continue;
+        }
+        failed += 1;
+        println!("❌ {}", fixture.display());
+        for expectation in &report.missing {
+            println!("    missing    {}", expectation);
+        }
+        for finding in &report.unexpected {
+            println!("    unexpected {}", finding);
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} fixture(s) failed",
+            failed,
+            fixtures.len()
+        ));
+    }
+    println!("🎉 All {} fixture(s) passed", fixtures.len());
+    Ok(())
+}
+
+/// Hooks written by `install-hook` and the `hook` arguments they run with.
+const HOOKS: [(&str, &str); 2] = [
+    ("pre-commit", "hook"),
+    ("commit-msg", "hook --commit-msg \"$1\""),
+];
+
+/// Writes `pre-commit` and `commit-msg` hooks that run this executable's `hook` subcommand.
+fn install_hook(args: &InstallHookArgs, config: &MergedConfig) -> Result<(), String> {
+    let inspector =
+        GitInspector::new(".").map_err(|e| format!("Failed to open git repository: {}", e))?;
+    let hooks_dir = inspector.hooks_dir();
+    for (name, _) in HOOKS {
+        let hook = hooks_dir.join(name);
+        if hook.exists()
+            && !args.force
+            && !fs::read_to_string(&hook).is_ok_and(|content| content.contains(HOOK_MARKER))
+        {
+            return Err(format!(
+                "'{}' already exists, use --force to overwrite it",
+                hook.display()
+            ));
+        }
+    }
+
+    let executable = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate grumpy_clippy executable: {}", e))?;
+    let mut command = shell_quote(&executable.to_string_lossy());
+    if let Some(config_file) = &config.config_file {
+        let config_file = fs::canonicalize(config_file)
+            .map_err(|e| format!("Failed to resolve '{}': {}", config_file, e))?;
+        command.push_str(" --config-file ");
+        command.push_str(&shell_quote(&config_file.to_string_lossy()));
+    }
+
+    fs::create_dir_all(&hooks_dir)
+        .map_err(|e| format!("Failed to create '{}': {}", hooks_dir.display(), e))?;
+    for (name, hook_args) in HOOKS {
+        let hook = hooks_dir.join(name);
+        let script = format!(
+            "#!/bin/sh\n{}\nexec {} {}\n",
+            HOOK_MARKER, command, hook_args
+        );
+        fs::write(&hook, script)
+            .map_err(|e| format!("Failed to write '{}': {}", hook.display(), e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to make '{}' executable: {}", hook.display(), e))?;
+        }
+        println!("🪝 Installed {} hook '{}'", name, hook.display());
+    }
+    Ok(())
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Analyzes the staged version of all staged Rust files and fails on `deny` findings.
+fn run_hook(config: &MergedConfig) -> Result<(), String> {
+    let inspector =
+        GitInspector::new(".").map_err(|e| format!("Failed to open git repository: {}", e))?;
+    let rules = load_custom_rules_from_toml(&config.custom_rules)?.unwrap_or_default();
+    let baseline = match &config.baseline_file {
+        Some(path) => Some(Baseline::from_file(Path::new(path)).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let staged_files = inspector
+        .list_staged_files()
+        .map_err(|e| format!("Failed to list staged files: {}", e))?;
+
+    let mut denied = 0;
+    for file in staged_files.iter().filter(|file| {
+        file.extension().is_some_and(|ext| ext == "rs")
+            && !shall_be_ignored(file, &config.ignore_patterns)
+    }) {
+        let source = inspector
+            .staged_source(file)
+            .map_err(|e| format!("Failed to read staged '{}': {}", file.display(), e))?;
+        let config = &config.for_file(&workdir_path(file));
+        let (_, mut findings) = match analyze_source_complexity(
+            &source,
+            &config.grumpiness_level,
+            &config.max_function_size,
+            &config.max_complexity,
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("⚠️ Skipping '{}': {}", file.display(), e);
+                continue;
+            }
+        };
+        let (_, rule_findings) =
+            apply_rules_by(&rules, &source, &|_| config.grumpiness_level.clone())?;
+        findings.extend(rule_findings);
+        findings.retain(|finding| config.reports(&finding.rule));
+
+        let relative_path = baseline_path(file);
+        for finding in findings.iter().filter(|f| {
+            !baseline
+                .as_ref()
+                .is_some_and(|b| b.contains(&relative_path, f))
+        }) {
+            let deny = finding.severity == Severity::Deny || config.deny.contains(&finding.rule);
+            if deny {
+                denied += 1;
+            }
+            let location = finding
+                .line
+                .map(|line| format!("{}:{}", file.display(), line))
+                .unwrap_or_else(|| file.display().to_string());
+            println!(
+                "{} {} [{}] {}",
+                if deny { "⛔" } else { "⚠️" },
+                location,
+                finding.rule,
+                finding.message
+            );
+        }
+    }
+
+    if denied > 0 {
+        return Err(pre_commit::blocked(&config.grumpiness_level, denied));
+    }
+    println!("{}", pre_commit::passed(&config.grumpiness_level));
+    Ok(())
+}
+
+/// Checks the commit message git is about to use and fails if it breaks a convention.
+fn check_commit_message(path: &Path, config: &MergedConfig) -> Result<(), String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read commit message '{}': {}", path.display(), e))?;
+    let findings = lint_commit_message(&raw, &config.commit_msg, &config.grumpiness_level)?;
+    for finding in &findings {
+        println!("⛔ [{}] {}", finding.rule, finding.message);
+    }
+    if !findings.is_empty() {
+        return Err(commit_message::blocked(
+            &config.grumpiness_level,
+            findings.len(),
+        ));
+    }
+    println!("{}", commit_message::passed(&config.grumpiness_level));
+    Ok(())
+}
+
+/// Prints the files or functions that change most often weighted by their complexity.
+fn show_hotspots(args: &HotspotsArgs) -> Result<(), String> {
+    let inspector =
+        GitInspector::new(".").map_err(|e| format!("Failed to open git repository: {}", e))?;
+    let mut hotspots = find_hotspots(&inspector, args.days, args.files)?;
+    hotspots.truncate(args.limit);
+
+    match args.format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&hotspots).map_err(|e| e.to_string())?
+        ),
+        OutputFormat::Text => {
+            if hotspots.is_empty() {
+                println!("🧊 No Rust changes in the last {} days", args.days);
+                return Ok(());
+            }
+            println!(
+                "🔥 Hotspots of the last {} days (commits × complexity):",
+                args.days
+            );
+            for (rank, hotspot) in hotspots.iter().enumerate() {
+                let name = match &hotspot.function {
+                    Some(function) => format!("{}: {}", hotspot.path, function),
+                    None => hotspot.path.clone(),
+                };
+                println!(
+                    "{:>3}. {:<60} {:>5}  ({} commits × complexity {})",
+                    rank + 1,
+                    name,
+                    hotspot.score,
+                    hotspot.commits,
+                    hotspot.complexity
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints who owns the code of every directory and how many people it depends on.
+fn show_ownership(args: &OwnershipArgs) -> Result<(), String> {
+    let inspector =
+        GitInspector::new(".").map_err(|e| format!("Failed to open git repository: {}", e))?;
+    let roots = args
+        .paths
+        .iter()
+        .map(|path| {
+            inspector
+                .relative_path(path)
+                .map_err(|e| format!("Invalid path '{}': {}", path, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let report = ownership_report(&inspector, &roots, args.depth, args.inactive_months)?;
+
+    match args.format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        ),
+        OutputFormat::Text => {
+            for directory in &report {
+                println!(
+                    "📂 {:<40} {:>6} lines  {:>3} author(s)  top: {} ({:.1}%)  bus factor: {}{}",
+                    directory.path,
+                    directory.lines,
+                    directory.authors,
+                    directory.top_author,
+                    directory.top_author_share,
+                    directory.bus_factor,
+                    if directory.inactive_authors.is_empty() {
+                        String::new()
+                    } else {
+                        format!("  inactive: {}", directory.inactive_authors.join(", "))
+                    }
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints the effective settings as config file lines, each with the layer it came from.
+fn show_config(config: &MergedConfig) -> Result<(), String> {
+    for (key, value) in config.settings() {
+        let origin = config
+            .origins
+            .get(key)
+            .map(String::as_str)
+            .unwrap_or("default");
+        let line = match value {
+            Some(value) => format!("{} = {}", key, value),
+            None => format!("# {} is not set", key),
+        };
+        println!("{:<48} # {}", line, origin);
+    }
+    Ok(())
+}
+
+/// Prints why a rule complains and how to make it happy.
+fn show_explanation(args: &ExplainArgs, config: &MergedConfig) -> Result<(), String> {
+    print!("{}", explain(&args.rule_id, &config.custom_rules)?);
+    Ok(())
+}
+
+/// Recursively collects `.rs` files below `path`, skipping ignored paths.
+fn collect_rust_files(
+    path: &Path,
+    ignore_patterns: &[String],
+    files: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    if shall_be_ignored(path, ignore_patterns) {
+        return Ok(());
+    }
+    if path.is_file() {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    let entries =
+        fs::read_dir(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let mut children: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    children.sort();
+    for child in children {
+        collect_rust_files(&child, ignore_patterns, files)?;
+    }
+    Ok(())
+}
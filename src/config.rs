@@ -5,18 +5,43 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+pub mod layers;
+
 /// Enum representing different grumpiness level
 #[derive(Debug, Clone, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(try_from = "String")]
 pub enum GrumpinessLevel {
     Mild,
     Sarcastic,
     Rude,
+    /// A personality declared in a message catalog (e.g. `pirate`), named in lowercase
+    /// snake case
+    Personality(String),
 }
 
 impl fmt::Display for GrumpinessLevel {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            GrumpinessLevel::Personality(name) => write!(f, "{}", name),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl GrumpinessLevel {
+    /// The level `steps` levels grumpier than this one, at most `Rude`. Personalities keep
+    /// their character.
+    pub fn escalated(&self, steps: u32) -> GrumpinessLevel {
+        let levels = [
+            GrumpinessLevel::Mild,
+            GrumpinessLevel::Sarcastic,
+            GrumpinessLevel::Rude,
+        ];
+        let Some(current) = levels.iter().position(|level| level == self) else {
+            return self.clone();
+        };
+        let escalated = (current + steps as usize).min(levels.len() - 1);
+        levels[escalated].clone()
     }
 }
 
@@ -28,12 +53,88 @@ impl FromStr for GrumpinessLevel {
             "mild" => Ok(GrumpinessLevel::Mild),
             "sarcastic" => Ok(GrumpinessLevel::Sarcastic),
             "rude" => Ok(GrumpinessLevel::Rude),
+            _ if is_personality_name(s) => Ok(GrumpinessLevel::Personality(s.to_string())),
             _ => Err(ConfigError::InvalidGrumpinessLevel(s.to_string())),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl TryFrom<String> for GrumpinessLevel {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Personalities are named like `drill_sergeant`: lowercase letters, digits and underscores.
+fn is_personality_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Which part of a changed file findings are reported for
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisScope {
+    /// Report findings of the whole file
+    #[default]
+    File,
+    /// Only report findings overlapping the lines changed according to git
+    Diff,
+}
+
+impl fmt::Display for AnalysisScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for AnalysisScope {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "file" => Ok(AnalysisScope::File),
+            "diff" => Ok(AnalysisScope::Diff),
+            _ => Err(ConfigError::InvalidScope(s.to_string())),
+        }
+    }
+}
+
+/// Output format of the reporting subcommands
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(ConfigError::InvalidOutputFormat(s.to_string())),
+        }
+    }
+}
+
+/// How one of several variants of a message is picked
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantSelection {
+    #[default]
+    Random,
+    RoundRobin,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct FileConfig {
     pub grumpiness_level: Option<GrumpinessLevel>,
     pub verbose: Option<bool>,
@@ -44,6 +145,64 @@ pub struct FileConfig {
     pub custom_rules: Option<String>,
     pub git_integration: Option<bool>,
     pub rules_file: Option<String>,
+    pub baseline_file: Option<String>,
+    pub message_catalog: Option<String>,
+    pub locale: Option<String>,
+    /// How message variants are picked: `random` or `round_robin`
+    pub message_variants: Option<VariantSelection>,
+    /// Seed of the random message variant selection, for reproducible output
+    pub message_seed: Option<u64>,
+    /// Get grumpier about findings that are ignored save after save
+    pub adaptive_grumpiness: Option<bool>,
+    /// Where the unfixed findings are remembered, next to the baseline file by default
+    pub offenses_file: Option<String>,
+    pub fix: Option<bool>,
+    pub scope: Option<AnalysisScope>,
+    pub deny: Option<Vec<String>>,
+    pub git: Option<GitFileConfig>,
+    pub commit_msg: Option<CommitMsgFileConfig>,
+    pub overrides: Option<Vec<OverrideFileConfig>>,
+}
+
+/// `[git]` section of the config file, only used with `git_integration` enabled
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GitFileConfig {
+    pub stale_days: Option<u64>,
+    pub check_stale: Option<bool>,
+    pub report_author: Option<bool>,
+    /// What `scope = "diff"` compares against: `index`, `HEAD` or a branch name
+    pub diff_base: Option<String>,
+    /// Report who last touched the lines of each finding
+    pub blame_findings: Option<bool>,
+    /// Only report findings on lines last touched by the configured git user
+    pub only_mine: Option<bool>,
+    /// Attach the owners from `CODEOWNERS` to findings and warn about unowned files
+    pub codeowners: Option<bool>,
+}
+
+/// `[commit_msg]` section of the config file, used by the commit message checks
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommitMsgFileConfig {
+    /// Allowed conventional commit types; an empty list disables the type check
+    pub types: Option<Vec<String>>,
+    pub max_subject_length: Option<usize>,
+    pub max_body_line_length: Option<usize>,
+    /// Regex every message must match somewhere, e.g. `"[A-Z]+-[0-9]+"`
+    pub issue_key: Option<String>,
+}
+
+/// `[[overrides]]` section of the config file: settings for the files matching `path`
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct OverrideFileConfig {
+    /// Files the settings apply to, relative to the working directory, written like in
+    /// `CODEOWNERS` (e.g. `tests/` or `src/**/generated_*.rs`)
+    pub path: String,
+    pub grumpiness_level: Option<GrumpinessLevel>,
+    pub max_function_size: Option<u8>,
+    pub max_complexity: Option<u8>,
+    /// Ids of the rules reported for these files (e.g. `["complexity", "no_todo_comments"]`);
+    /// all rules if not given
+    pub rules: Option<Vec<String>>,
 }
 
 impl FileConfig {
@@ -67,6 +226,11 @@ pub enum ConfigError {
     ValueTooSmall(String, u8, u8),
     MissingWatchFiles,
     InvalidGrumpinessLevel(String),
+    InvalidScope(String),
+    InvalidPattern(String, String),
+    InvalidOutputFormat(String),
+    UnknownPersonality(String, Vec<String>),
+    InvalidEnvVar(String, String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -90,10 +254,31 @@ impl std::fmt::Display for ConfigError {
             ConfigError::InvalidGrumpinessLevel(value) => {
                 write!(
                     f,
-                    "GrumpinessLevel must be 'mild', 'sarcastic', or 'rude', but got {}",
+                    "GrumpinessLevel must be 'mild', 'sarcastic', 'rude' or a personality in snake_case, but got {}",
+                    value
+                )
+            }
+            ConfigError::InvalidScope(value) => {
+                write!(f, "Scope must be 'file' or 'diff', but got {}", value)
+            }
+            ConfigError::InvalidPattern(param, error) => {
+                write!(f, "{} is not a valid regex: {}", param, error)
+            }
+            ConfigError::InvalidOutputFormat(value) => {
+                write!(
+                    f,
+                    "Output format must be 'text' or 'json', but got {}",
                     value
                 )
             }
+            ConfigError::UnknownPersonality(name, available) => {
+                write!(
+                    f,
+                    "No messages for grumpiness level '{}'. Available: {}",
+                    name,
+                    available.join(", ")
+                )
+            }
             ConfigError::ValueTooSmall(param, value, min_value) => {
                 write!(
                     f,
@@ -101,6 +286,9 @@ impl std::fmt::Display for ConfigError {
                     param, min_value, value
                 )
             }
+            ConfigError::InvalidEnvVar(name, error) => {
+                write!(f, "Environment variable {} is invalid: {}", name, error)
+            }
             ConfigError::MissingWatchFiles => {
                 write!(f, "Watch files shall not be empty!")
             }
@@ -112,7 +300,7 @@ impl std::fmt::Display for ConfigError {
 #[cfg(test)]
 mod tests {
     use super::FileConfig;
-    use crate::config::{ConfigError, GrumpinessLevel};
+    use crate::config::{AnalysisScope, ConfigError, GrumpinessLevel};
     use std::io::Write;
     use std::{fs::File, path::Path, str::FromStr};
     use tempfile::tempdir;
@@ -134,6 +322,26 @@ mod tests {
         assert!(GrumpinessLevel::from_str("NOT_A_GUMPINESS_LEVEL").is_err())
     }
 
+    #[test]
+    fn test_personality_parsing() {
+        assert_eq!(
+            GrumpinessLevel::from_str("drill_sergeant").unwrap(),
+            GrumpinessLevel::Personality("drill_sergeant".into())
+        );
+        assert!(GrumpinessLevel::from_str("pirate captain").is_err());
+        assert!(GrumpinessLevel::from_str("_pirate").is_err());
+
+        let config: FileConfig = toml::from_str(r#"grumpiness_level = "pirate""#).unwrap();
+        assert_eq!(
+            config.grumpiness_level,
+            Some(GrumpinessLevel::Personality("pirate".into()))
+        );
+        assert_eq!(
+            GrumpinessLevel::Personality("pirate".into()).escalated(2),
+            GrumpinessLevel::Personality("pirate".into())
+        );
+    }
+
     #[test]
     fn test_valide_file_config_deserialization() {
         let toml_data = r#"
@@ -153,6 +361,54 @@ mod tests {
         assert!(config.rules_file.is_none());
     }
 
+    #[test]
+    fn test_git_section_deserialization() {
+        let toml_data = r#"
+            git_integration = true
+
+            [git]
+            stale_days = 30
+            report_author = false
+        "#;
+
+        let config: FileConfig = toml::from_str(toml_data).unwrap();
+        let git = config.git.unwrap();
+        assert_eq!(git.stale_days, Some(30));
+        assert_eq!(git.check_stale, None);
+        assert_eq!(git.report_author, Some(false));
+    }
+
+    #[test]
+    fn test_scope_parsing() {
+        assert_eq!(
+            AnalysisScope::from_str("DIFF").unwrap(),
+            AnalysisScope::Diff
+        );
+        assert!(AnalysisScope::from_str("line").is_err());
+
+        let config: FileConfig =
+            toml::from_str("scope = \"diff\"\n[git]\ndiff_base = \"main\"\n").unwrap();
+        assert_eq!(config.scope, Some(AnalysisScope::Diff));
+        assert_eq!(config.git.unwrap().diff_base.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_commit_msg_section_deserialization() {
+        let toml_data = r#"
+            [commit_msg]
+            types = ["feat", "fix"]
+            max_subject_length = 50
+            issue_key = "[A-Z]+-[0-9]+"
+        "#;
+
+        let config: FileConfig = toml::from_str(toml_data).unwrap();
+        let commit_msg = config.commit_msg.unwrap();
+        assert_eq!(commit_msg.types.unwrap(), vec!["feat", "fix"]);
+        assert_eq!(commit_msg.max_subject_length, Some(50));
+        assert_eq!(commit_msg.max_body_line_length, None);
+        assert_eq!(commit_msg.issue_key.as_deref(), Some("[A-Z]+-[0-9]+"));
+    }
+
     #[test]
     fn test_invalid_file_path_config_file() {
         let file_path = Path::new("file_does_not_exists.toml");
@@ -0,0 +1,563 @@
+//! Layered configuration files.
+//!
+//! Settings are collected from several places and merged, from lowest to highest precedence:
+//!
+//! 1. the user config `~/.config/grumpy_clippy/config.toml` (or below `$XDG_CONFIG_HOME`)
+//! 2. `[workspace.metadata.grumpy_clippy]` and `[package.metadata.grumpy_clippy]` of every
+//!    `Cargo.toml` from the repository root down to the working directory
+//! 3. every `.grumpyclippy.toml` from the repository root down to the working directory
+//! 4. the file given with `--config-file`
+//! 5. `GRUMPY_CLIPPY_*` environment variables, e.g. `GRUMPY_CLIPPY_MAX_COMPLEXITY=10` or
+//!    `GRUMPY_CLIPPY_GIT__STALE_DAYS=30` (`__` separates the section from the key)
+//!
+//! CLI flags override all of them (see `MergedConfig::from_sources`). Layers are merged key
+//! by key, so a layer only needs to contain the settings it changes; sections like `[git]`
+//! are merged the same way, lists are replaced as a whole.
+//!
+//! Relative paths in discovered layers (see [`PATH_SETTINGS`]) are relative to the file that
+//! declares them, like `rules.toml` next to a parent directory's `.grumpyclippy.toml`. Paths
+//! in the `--config-file` and environment variables stay relative to the working directory.
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Table;
+
+use super::{ConfigError, FileConfig};
+
+/// Name of the config file searched from the working directory up to the repository root
+pub const CONFIG_FILE_NAME: &str = ".grumpyclippy.toml";
+
+/// Key of the settings in the `metadata` tables of `Cargo.toml`
+const METADATA_KEY: &str = "grumpy_clippy";
+
+/// Prefix of the environment variables overriding settings
+pub const ENV_PREFIX: &str = "GRUMPY_CLIPPY_";
+
+/// Keys of all settings, with sections separated by `.`
+pub const SETTINGS: &[&str] = &[
+    "grumpiness_level",
+    "verbose",
+    "watch_files",
+    "ignore_patterns",
+    "max_function_size",
+    "max_complexity",
+    "custom_rules",
+    "git_integration",
+    "rules_file",
+    "baseline_file",
+    "message_catalog",
+    "locale",
+    "message_variants",
+    "message_seed",
+    "adaptive_grumpiness",
+    "offenses_file",
+    "fix",
+    "scope",
+    "deny",
+    "git.stale_days",
+    "git.check_stale",
+    "git.report_author",
+    "git.diff_base",
+    "git.blame_findings",
+    "git.only_mine",
+    "git.codeowners",
+    "commit_msg.types",
+    "commit_msg.max_subject_length",
+    "commit_msg.max_body_line_length",
+    "commit_msg.issue_key",
+    "overrides",
+];
+
+/// Keys of the settings holding paths
+const PATH_SETTINGS: &[&str] = &[
+    "custom_rules",
+    "rules_file",
+    "baseline_file",
+    "offenses_file",
+    "message_catalog",
+];
+
+/// Where the settings of a layer were read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerSource {
+    User(PathBuf),
+    WorkspaceMetadata(PathBuf),
+    PackageMetadata(PathBuf),
+    ConfigFile(PathBuf),
+    Environment,
+}
+
+impl fmt::Display for LayerSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerSource::User(path) => write!(f, "user config '{}'", path.display()),
+            LayerSource::WorkspaceMetadata(path) => write!(
+                f,
+                "[workspace.metadata.{}] in '{}'",
+                METADATA_KEY,
+                path.display()
+            ),
+            LayerSource::PackageMetadata(path) => write!(
+                f,
+                "[package.metadata.{}] in '{}'",
+                METADATA_KEY,
+                path.display()
+            ),
+            LayerSource::ConfigFile(path) => write!(f, "'{}'", path.display()),
+            LayerSource::Environment => write!(f, "{}* environment variables", ENV_PREFIX),
+        }
+    }
+}
+
+/// Settings of one place, as written there
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    pub source: LayerSource,
+    pub table: Table,
+}
+
+impl ConfigLayer {
+    /// Reads a whole file as layer, checking that it holds valid settings.
+    fn from_file(source: LayerSource, path: &Path) -> Result<Self, ConfigError> {
+        FileConfig::from_file(path)?;
+        Ok(ConfigLayer {
+            source,
+            table: read_table(path)?,
+        })
+    }
+
+    /// Resolves the relative paths of the layer against `dir`, the directory of the file
+    /// declaring them. Paths below `cwd` are kept relative to it.
+    fn with_paths_relative_to(mut self, dir: &Path, cwd: &Path) -> Self {
+        for key in PATH_SETTINGS {
+            if let Some(toml::Value::String(path)) = self.table.get_mut(*key)
+                && Path::new(path).is_relative()
+            {
+                let resolved = dir.join(&*path);
+                let resolved = resolved.strip_prefix(cwd).unwrap_or(&resolved);
+                *path = resolved.to_string_lossy().into_owned();
+            }
+        }
+        self
+    }
+}
+
+fn read_table(path: &Path) -> Result<Table, ConfigError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))?;
+    content
+        .parse::<Table>()
+        .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))
+}
+
+/// Makes sure the layer deserializes on its own, so errors name the file they are in.
+fn checked(source: LayerSource, table: Table, path: &Path) -> Result<ConfigLayer, ConfigError> {
+    toml::Value::Table(table.clone())
+        .try_into::<FileConfig>()
+        .map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e.to_string()))?;
+    Ok(ConfigLayer { source, table })
+}
+
+/// Path of the user-global config file, whether it exists or not.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("grumpy_clippy").join("config.toml"))
+}
+
+/// Directories from the repository root (the closest ancestor containing `.git`) down to
+/// `cwd`. Outside of a repository, only `cwd` itself is searched.
+fn search_dirs(cwd: &Path) -> Vec<&Path> {
+    let ancestors: Vec<&Path> = cwd.ancestors().collect();
+    let mut dirs = match ancestors.iter().position(|dir| dir.join(".git").exists()) {
+        Some(root) => ancestors[..=root].to_vec(),
+        None => vec![cwd],
+    };
+    dirs.reverse();
+    dirs
+}
+
+/// Collects the config layers for `cwd` in ascending precedence (see module docs).
+///
+/// Layers that cannot be read are skipped and their errors returned alongside, so one broken
+/// file does not take the others down.
+pub fn discover_layers(
+    cwd: &Path,
+    user_config: Option<&Path>,
+    config_file: Option<&Path>,
+) -> (Vec<ConfigLayer>, Vec<ConfigError>) {
+    let mut layers = vec![];
+    let mut errors = vec![];
+    let dirs = search_dirs(cwd);
+
+    let declared_in = |path: &Path| path.parent().unwrap_or(cwd).to_path_buf();
+
+    if let Some(path) = user_config.filter(|path| path.exists()) {
+        push(
+            ConfigLayer::from_file(LayerSource::User(path.to_path_buf()), path)
+                .map(|layer| layer.with_paths_relative_to(&declared_in(path), cwd)),
+            &mut layers,
+            &mut errors,
+        );
+    }
+
+    for manifest in dirs.iter().map(|dir| dir.join("Cargo.toml")) {
+        if !manifest.exists() {
+            continue;
+        }
+        match read_table(&manifest) {
+            Ok(mut table) => {
+                for (section, source) in [
+                    (
+                        "workspace",
+                        LayerSource::WorkspaceMetadata(manifest.clone()),
+                    ),
+                    ("package", LayerSource::PackageMetadata(manifest.clone())),
+                ] {
+                    if let Some(toml::Value::Table(metadata)) = metadata_table(&mut table, section)
+                    {
+                        push(
+                            checked(source, metadata, &manifest).map(|layer| {
+                                layer.with_paths_relative_to(&declared_in(&manifest), cwd)
+                            }),
+                            &mut layers,
+                            &mut errors,
+                        );
+                    }
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    for path in dirs.iter().map(|dir| dir.join(CONFIG_FILE_NAME)) {
+        if path.exists() {
+            push(
+                ConfigLayer::from_file(LayerSource::ConfigFile(path.clone()), &path)
+                    .map(|layer| layer.with_paths_relative_to(&declared_in(&path), cwd)),
+                &mut layers,
+                &mut errors,
+            );
+        }
+    }
+
+    if let Some(path) = config_file {
+        push(
+            ConfigLayer::from_file(LayerSource::ConfigFile(path.to_path_buf()), path),
+            &mut layers,
+            &mut errors,
+        );
+    }
+
+    (layers, errors)
+}
+
+/// Files settings are read from for `cwd`, whether they exist yet or not.
+pub fn config_files(
+    cwd: &Path,
+    user_config: Option<&Path>,
+    config_file: Option<&Path>,
+) -> Vec<PathBuf> {
+    let dirs = search_dirs(cwd);
+    user_config
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(dirs.iter().map(|dir| dir.join("Cargo.toml")))
+        .chain(dirs.iter().map(|dir| dir.join(CONFIG_FILE_NAME)))
+        .chain(config_file.map(Path::to_path_buf))
+        .collect()
+}
+
+fn push(
+    layer: Result<ConfigLayer, ConfigError>,
+    layers: &mut Vec<ConfigLayer>,
+    errors: &mut Vec<ConfigError>,
+) {
+    match layer {
+        Ok(layer) => layers.push(layer),
+        Err(e) => errors.push(e),
+    }
+}
+
+/// Takes `[<section>.metadata.grumpy_clippy]` out of a parsed `Cargo.toml`.
+fn metadata_table(manifest: &mut Table, section: &str) -> Option<toml::Value> {
+    manifest
+        .get_mut(section)?
+        .as_table_mut()?
+        .get_mut("metadata")?
+        .as_table_mut()?
+        .remove(METADATA_KEY)
+}
+
+/// Name of the environment variable overriding the setting `key` (e.g. `git.stale_days`).
+pub fn env_var_name(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.replace('.', "__").to_uppercase())
+}
+
+/// Collects the settings overridden by `GRUMPY_CLIPPY_*` variables among `vars` into a layer.
+///
+/// Values are read as TOML (`10`, `true`, `[".rs", ".toml"]`) or, if that does not fit the
+/// setting, as plain string or comma separated list. Unknown or invalid variables are
+/// skipped and their errors returned alongside.
+pub fn env_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> (Option<ConfigLayer>, Vec<ConfigError>) {
+    let mut table = Table::new();
+    let mut errors = vec![];
+    for (name, raw) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let key = key.to_lowercase().replace("__", ".");
+        if !SETTINGS.contains(&key.as_str()) {
+            errors.push(ConfigError::InvalidEnvVar(name, "no such setting".into()));
+            continue;
+        }
+        match env_value(&key, &raw) {
+            Ok(value) => merge_table(&mut table, value),
+            Err(e) => errors.push(ConfigError::InvalidEnvVar(name, e)),
+        }
+    }
+    let layer = (!table.is_empty()).then_some(ConfigLayer {
+        source: LayerSource::Environment,
+        table,
+    });
+    (layer, errors)
+}
+
+/// The first interpretation of `raw` that is a valid value of the setting `key`.
+fn env_value(key: &str, raw: &str) -> Result<Table, String> {
+    let typed = toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"));
+    let list = raw
+        .split(',')
+        .map(|item| toml::Value::String(item.trim().to_string()))
+        .collect();
+    let mut error = String::new();
+    for value in typed.into_iter().chain([
+        toml::Value::String(raw.to_string()),
+        toml::Value::Array(list),
+    ]) {
+        let table = nested(key, value);
+        match toml::Value::Table(table.clone()).try_into::<FileConfig>() {
+            Ok(_) => return Ok(table),
+            Err(e) => error = e.to_string(),
+        }
+    }
+    Err(error.trim().to_string())
+}
+
+/// A table holding `value` at the dotted `key`.
+fn nested(key: &str, value: toml::Value) -> Table {
+    let mut parts = key.rsplit('.');
+    let last = parts.next().unwrap_or(key);
+    let innermost = Table::from_iter([(last.to_string(), value)]);
+    parts.fold(innermost, |table, section| {
+        Table::from_iter([(section.to_string(), toml::Value::Table(table))])
+    })
+}
+
+/// Where the setting `key` was taken from: the last layer setting it, if any.
+pub fn origin(layers: &[ConfigLayer], key: &str) -> Option<String> {
+    layers
+        .iter()
+        .rev()
+        .find(|layer| {
+            key.split('.')
+                .try_fold(&layer.table, |table, part| match table.get(part) {
+                    Some(toml::Value::Table(section)) => Ok(section),
+                    Some(_) => Err(true),
+                    None => Err(false),
+                })
+                .is_err_and(|found| found)
+        })
+        .map(|layer| match layer.source {
+            LayerSource::Environment => env_var_name(key),
+            _ => layer.source.to_string(),
+        })
+}
+
+/// Merges the layers, each one overriding the settings of the ones before it.
+pub fn merge_layers(layers: &[ConfigLayer]) -> Result<FileConfig, ConfigError> {
+    let mut merged = Table::new();
+    for layer in layers {
+        merge_table(&mut merged, layer.table.clone());
+    }
+    toml::Value::Table(merged)
+        .try_into()
+        .map_err(|e| ConfigError::InvalidFile(PathBuf::from(CONFIG_FILE_NAME), e.to_string()))
+}
+
+fn merge_table(base: &mut Table, top: Table) {
+    for (key, value) in top {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(top)) => merge_table(base, top),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GrumpinessLevel;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_layers_are_discovered_and_merged_by_precedence() {
+        let root = tempdir().unwrap();
+        let crate_dir = root.path().join("crates/app");
+        fs::create_dir_all(root.path().join(".git")).unwrap();
+        fs::create_dir_all(&crate_dir).unwrap();
+        let user = root.path().join("user.toml");
+        fs::write(&user, "max_complexity = 10\nverbose = true\n").unwrap();
+        fs::write(
+            root.path().join("Cargo.toml"),
+            "[workspace]\n[workspace.metadata.grumpy_clippy]\nmax_complexity = 20\n[git]\nnot = \"metadata\"\n",
+        )
+        .unwrap();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n[package.metadata.grumpy_clippy]\ngrumpiness_level = \"rude\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.path().join(CONFIG_FILE_NAME),
+            "max_complexity = 30\n[git]\nstale_days = 7\ncheck_stale = false\n",
+        )
+        .unwrap();
+        fs::write(crate_dir.join(CONFIG_FILE_NAME), "[git]\nstale_days = 14\n").unwrap();
+
+        let (layers, errors) = discover_layers(&crate_dir, Some(&user), None);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            layers.iter().map(|l| l.source.clone()).collect::<Vec<_>>(),
+            [
+                LayerSource::User(user.clone()),
+                LayerSource::WorkspaceMetadata(root.path().join("Cargo.toml")),
+                LayerSource::PackageMetadata(crate_dir.join("Cargo.toml")),
+                LayerSource::ConfigFile(root.path().join(CONFIG_FILE_NAME)),
+                LayerSource::ConfigFile(crate_dir.join(CONFIG_FILE_NAME)),
+            ]
+        );
+
+        let config = merge_layers(&layers).unwrap();
+        assert_eq!(config.max_complexity, Some(30));
+        assert_eq!(config.verbose, Some(true));
+        assert_eq!(config.grumpiness_level, Some(GrumpinessLevel::Rude));
+        let git = config.git.unwrap();
+        assert_eq!(git.stale_days, Some(14));
+        assert_eq!(git.check_stale, Some(false));
+    }
+
+    #[test]
+    fn test_paths_are_relative_to_the_declaring_file() {
+        let root = tempdir().unwrap();
+        let crate_dir = root.path().join("crates/app");
+        fs::create_dir_all(root.path().join(".git")).unwrap();
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(
+            root.path().join(CONFIG_FILE_NAME),
+            "custom_rules = \"rules.toml\"\nbaseline_file = \"/abs/baseline.json\"\n",
+        )
+        .unwrap();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n[package.metadata.grumpy_clippy]\nmessage_catalog = \"assets/messages.toml\"\n",
+        )
+        .unwrap();
+
+        let (layers, errors) = discover_layers(&crate_dir, None, None);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let config = merge_layers(&layers).unwrap();
+        assert_eq!(
+            config.custom_rules.map(PathBuf::from),
+            Some(root.path().join("rules.toml"))
+        );
+        assert_eq!(config.baseline_file.as_deref(), Some("/abs/baseline.json"));
+        assert_eq!(
+            config.message_catalog.as_deref(),
+            Some("assets/messages.toml")
+        );
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let vars = [
+            ("GRUMPY_CLIPPY_MAX_COMPLEXITY", "10"),
+            ("GRUMPY_CLIPPY_WATCH_FILES", ".rs, .md"),
+            ("GRUMPY_CLIPPY_LOCALE", "de"),
+            ("GRUMPY_CLIPPY_GIT__STALE_DAYS", "30"),
+            ("GRUMPY_CLIPPY_GIT__DIFF_BASE", "main"),
+            ("GRUMPY_CLIPPY_MAX_FUNCTION_SIZE", "huge"),
+            ("GRUMPY_CLIPPY_MAX_COMPLEXTY", "3"),
+            ("HOME", "/root"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let (layer, errors) = env_layer(vars);
+        let names: Vec<_> = errors
+            .iter()
+            .map(|e| match e {
+                ConfigError::InvalidEnvVar(name, _) => name.as_str(),
+                _ => panic!("Unexpected error {}", e),
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "GRUMPY_CLIPPY_MAX_FUNCTION_SIZE",
+                "GRUMPY_CLIPPY_MAX_COMPLEXTY"
+            ]
+        );
+
+        let file = ConfigLayer {
+            source: LayerSource::ConfigFile(PathBuf::from(CONFIG_FILE_NAME)),
+            table: "max_complexity = 5\nverbose = true\n".parse().unwrap(),
+        };
+        let layers = [file, layer.unwrap()];
+        let config = merge_layers(&layers).unwrap();
+        assert_eq!(config.max_complexity, Some(10));
+        assert_eq!(config.verbose, Some(true));
+        assert_eq!(config.watch_files.unwrap(), [".rs", ".md"]);
+        assert_eq!(config.locale.as_deref(), Some("de"));
+        let git = config.git.unwrap();
+        assert_eq!(git.stale_days, Some(30));
+        assert_eq!(git.diff_base.as_deref(), Some("main"));
+
+        assert_eq!(
+            origin(&layers, "git.stale_days").as_deref(),
+            Some("GRUMPY_CLIPPY_GIT__STALE_DAYS")
+        );
+        assert_eq!(
+            origin(&layers, "verbose").as_deref(),
+            Some("'.grumpyclippy.toml'")
+        );
+        assert_eq!(origin(&layers, "git.only_mine"), None);
+    }
+
+    #[test]
+    fn test_broken_layers_are_reported_and_skipped() {
+        let root = tempdir().unwrap();
+        fs::write(
+            root.path().join(CONFIG_FILE_NAME),
+            "max_complexity = \"high\"\n",
+        )
+        .unwrap();
+        let explicit = root.path().join("explicit.toml");
+        fs::write(&explicit, "max_complexity = 12\n").unwrap();
+
+        let (layers, errors) = discover_layers(root.path(), None, Some(&explicit));
+        assert!(matches!(errors[..], [ConfigError::InvalidFile(_, _)]));
+        assert_eq!(merge_layers(&layers).unwrap().max_complexity, Some(12));
+
+        let missing = root.path().join("missing.toml");
+        let (_, errors) = discover_layers(root.path(), Some(&missing), Some(&missing));
+        assert!(matches!(errors[..], [_, ConfigError::FileNotFoundError(_)]));
+    }
+}
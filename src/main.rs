@@ -1,11 +1,18 @@
 mod analyzer;
 mod app_state;
 mod cli;
+mod commands;
 mod config;
 mod logger;
 mod ui;
 mod watcher;
 
+use crate::analyzer::message_catalog::{
+    MessageCatalog, VariantSelector, init_catalog, is_supported_locale, missing_translations,
+    supported_locales,
+};
+use crate::config::ConfigError;
+use crate::config::layers::{discover_layers, env_layer, user_config_path};
 use crate::logger::config::Config as LoggerConfig;
 use crate::logger::init_logger;
 use app_state::new_shared_state;
@@ -50,12 +57,114 @@ fn initialize_logger() {
     info!("GrumpyClippy started successfully!");
 }
 
+fn load_config() -> Result<(cli::MergedConfig, Option<cli::Command>), String> {
+    let mut cli = argh::from_env::<cli::CliArgs>();
+    let command = cli.command.take();
+    let (merged_config, errors) = read_config(cli)?;
+    for e in errors {
+        eprintln!("Error reading config: {}", e);
+    }
+    merged_config.validate().map_err(|e| e.to_string())?;
+    load_message_catalog(&merged_config)?;
+
+    Ok((merged_config, command))
+}
+
+/// Reads the settings again after a config file changed. Other than at startup, a layer that
+/// cannot be read makes the whole config invalid, so the watcher keeps the settings it has.
+fn reload_config() -> Result<cli::MergedConfig, String> {
+    // The command line does not change while running, so parsing it again gives the same flags
+    let mut cli = argh::from_env::<cli::CliArgs>();
+    cli.command = None;
+    let (merged_config, errors) = read_config(cli)?;
+    if let Some(e) = errors.first() {
+        return Err(e.to_string());
+    }
+    merged_config.validate().map_err(|e| e.to_string())?;
+    load_message_catalog(&merged_config)?;
+    Ok(merged_config)
+}
+
+/// Merges the config layers of the working directory and the environment, with the CLI flags
+/// on top. Layers that cannot be read are left out and their errors returned alongside.
+fn read_config(cli: cli::CliArgs) -> Result<(cli::MergedConfig, Vec<ConfigError>), String> {
+    let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+    let (mut layers, mut errors) = discover_layers(
+        &cwd,
+        user_config_path().as_deref(),
+        cli.config_file.as_deref().map(Path::new),
+    );
+    let (env, env_errors) = env_layer(std::env::vars());
+    layers.extend(env);
+    errors.extend(env_errors);
+    for layer in &layers {
+        info!("Config read from {}", layer.source);
+    }
+
+    let merged_config = cli::MergedConfig::from_layers(cli, &layers)
+        .map_err(|e| e.to_string())?
+        .with_lang(std::env::var("LANG").ok().as_deref());
+    Ok((merged_config, errors))
+}
+
+/// Makes Clippy speak the configured locale, with the texts of the message catalog file on top.
+fn load_message_catalog(config: &cli::MergedConfig) -> Result<(), String> {
+    let locale = config.locale.as_str();
+    if is_supported_locale(locale) {
+        for missing in missing_translations(locale) {
+            warning!("Missing translation for locale '{}': {}", locale, missing);
+        }
+    } else {
+        info!(
+            "No messages for locale '{}', speaking English. Available: {}",
+            locale,
+            supported_locales().join(", ")
+        );
+    }
+    let mut catalog = match &config.message_catalog {
+        Some(path) => {
+            MessageCatalog::with_overrides(locale, Path::new(path)).map_err(|e| e.to_string())?
+        }
+        None => MessageCatalog::localized(locale),
+    };
+    let override_levels = config
+        .overrides
+        .iter()
+        .filter_map(|file_override| file_override.settings.grumpiness_level.as_ref());
+    for level in std::iter::once(&config.grumpiness_level).chain(override_levels) {
+        catalog.validate_level(level).map_err(|e| e.to_string())?;
+    }
+    catalog.select_variants(VariantSelector::new(
+        config.message_variants,
+        config.message_seed,
+    ));
+    init_catalog(catalog);
+    Ok(())
+}
+
 fn main() -> Result<(), eframe::Error> {
     // Initialize logger first
     initialize_logger();
 
+    let (merged_config, command) = match load_config() {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("❌ Config error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Subcommands run once, without watcher and GUI
+    if let Some(command) = command {
+        if let Err(e) = commands::run(command, &merged_config) {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Shared app state
-    let app_state = new_shared_state();
+    let app_state = new_shared_state(merged_config.custom_rules.clone());
 
     // Run the watcher in a background thread
     let running = setup_shutdown();
@@ -63,26 +172,9 @@ fn main() -> Result<(), eframe::Error> {
     let state_for_watcher = app_state.clone();
 
     std::thread::spawn(move || {
-        let cli = argh::from_env::<cli::CliArgs>();
-        let file_config = cli.config_file.as_deref().map(Path::new).and_then(|path| {
-            match config::FileConfig::from_file(path) {
-                Ok(cfg) => Some(cfg),
-                Err(e) => {
-                    eprintln!("Error reading config file: {}", e);
-                    None
-                }
-            }
-        });
-
-        let merged_config = cli::MergedConfig::from_sources(cli, file_config);
-
-        if let Err(e) = merged_config.validate() {
-            eprintln!("❌ Config error: {}", e);
-            run_flag.store(false, Ordering::SeqCst);
-            return;
-        }
-
-        if let Err(e) = watcher::start_watching(&merged_config, &run_flag, state_for_watcher) {
+        if let Err(e) =
+            watcher::start_watching(merged_config, reload_config, &run_flag, state_for_watcher)
+        {
             eprintln!("❌ Failed to start watcher: {}", e);
         }
     });
@@ -1,6 +1,7 @@
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver},
 };
 
 use eframe::{
@@ -10,12 +11,20 @@ use eframe::{
 };
 use egui::{Color32, TextureHandle};
 
+use crate::analyzer::explain::{Explanation, explain};
+use crate::analyzer::fix;
 use crate::app_state::SharedAppState;
 
 pub struct ClippyApp {
     clippy_texture: Option<TextureHandle>,
     state: SharedAppState,
     running: Arc<AtomicBool>,
+    /// Explanation shown in its own window, if requested
+    explanation: Option<Explanation>,
+    /// Explanation being looked up in the background, if requested
+    pending_explanation: Option<Receiver<Result<Explanation, String>>>,
+    /// Index of the finding chosen to be explained
+    selected_finding: usize,
 }
 
 impl ClippyApp {
@@ -25,6 +34,9 @@ impl ClippyApp {
             clippy_texture,
             state,
             running,
+            explanation: None,
+            pending_explanation: None,
+            selected_finding: 0,
         }
     }
 
@@ -62,6 +74,119 @@ impl ClippyApp {
             Color32::WHITE,
         );
     }
+
+    /// Applies the fix of the first fixable finding and reports the outcome in the bubble.
+    fn fix_current_finding(&self) {
+        let mut state = self.state.write().unwrap();
+        let (Some(path), Some(index)) = (
+            state.path.clone(),
+            state.findings.iter().position(|f| f.is_fixable()),
+        ) else {
+            return;
+        };
+        let finding = state.findings.remove(index);
+        state.message = match fix::fix_file(&path, &finding.edits) {
+            Ok(count) => format!("🔧 Applied {} fix(es) for '{}'", count, finding.rule),
+            Err(e) => format!("❌ Failed to apply fix: {}", e),
+        };
+    }
+
+    /// Starts looking up the explanation of the selected finding's rule. Explaining a clippy
+    /// lint runs cargo, so it is done on its own thread to keep the window responsive.
+    fn explain_selected_finding(&mut self, ctx: &egui::Context) {
+        let (rule, custom_rules) = {
+            let state = self.state.read().unwrap();
+            let rule = state
+                .findings
+                .get(self.selected_finding)
+                .map(|f| f.rule.clone());
+            (rule, state.custom_rules.clone())
+        };
+        let Some(rule) = rule else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            // The receiver is gone if the app shut down meanwhile
+            let _ = tx.send(explain(&rule, &custom_rules));
+            ctx.request_repaint();
+        });
+        self.pending_explanation = Some(rx);
+    }
+
+    /// Shows the explanation looked up in the background once it is there, or why there is none.
+    fn receive_explanation(&mut self) {
+        let Some(result) = self
+            .pending_explanation
+            .as_ref()
+            .and_then(|rx| rx.try_recv().ok())
+        else {
+            return;
+        };
+        self.pending_explanation = None;
+        match result {
+            Ok(explanation) => self.explanation = Some(explanation),
+            Err(e) => self.state.write().unwrap().message = format!("❌ {}", e),
+        }
+    }
+
+    /// Lets the user choose which of the reported findings to explain.
+    fn select_finding(&mut self, ui: &mut egui::Ui) {
+        let labels: Vec<String> = self
+            .state
+            .read()
+            .unwrap()
+            .findings
+            .iter()
+            .map(|finding| match finding.line {
+                Some(line) => format!("{} (line {})", finding.rule, line),
+                None => finding.rule.clone(),
+            })
+            .collect();
+        if self.selected_finding >= labels.len() {
+            self.selected_finding = 0;
+        }
+        if labels.len() > 1 {
+            egui::ComboBox::from_id_salt("finding")
+                .selected_text(&labels[self.selected_finding])
+                .show_index(ui, &mut self.selected_finding, labels.len(), |i| {
+                    labels[i].clone()
+                });
+        }
+    }
+
+    fn draw_explanation(&mut self, ctx: &egui::Context) {
+        let Some(explanation) = &self.explanation else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(format!("❓ {}", explanation.rule))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.label(explanation.rationale.trim());
+                    if let Some(bad) = &explanation.bad {
+                        ui.label("👎 Bad:");
+                        ui.code(bad.trim_matches('\n'));
+                    }
+                    if let Some(good) = &explanation.good {
+                        ui.label("👍 Good:");
+                        ui.code(good.trim_matches('\n'));
+                    }
+                    for tip in &explanation.tips {
+                        ui.label(format!("💡 {}", tip));
+                    }
+                    if let Some(docs) = &explanation.docs {
+                        ui.hyperlink_to("📖 Documentation", docs);
+                    }
+                });
+            });
+        if !open {
+            self.explanation = None;
+        }
+    }
 }
 
 impl App for ClippyApp {
@@ -84,11 +209,27 @@ impl App for ClippyApp {
                 // Optional: leave space or put a heading on the left
                 ui.vertical(|ui| {
                     ui.heading("Grumpy Clippy");
+                    let fixable = self.state.read().unwrap().fixable_finding().is_some();
+                    if fixable && ui.button("🔧 Fix it").clicked() {
+                        self.fix_current_finding();
+                    }
+                    let explainable = !self.state.read().unwrap().findings.is_empty();
+                    if explainable {
+                        self.select_finding(ui);
+                        if self.pending_explanation.is_some() {
+                            ui.add_enabled(false, egui::Button::new("⏳ Explaining..."));
+                        } else if ui.button("❓ Explain").clicked() {
+                            self.explain_selected_finding(ctx);
+                        }
+                    }
                 });
             });
 
             self.draw_speech_bubble(ui);
         });
+
+        self.receive_explanation();
+        self.draw_explanation(ctx);
     }
 }
 
@@ -1,11 +1,18 @@
 use super::error;
-use crate::analyzer::actions::handle_file_changes;
-use crate::analyzer::custom_rules;
+use crate::analyzer::actions::{handle_commit_message, handle_file_changes};
+use crate::analyzer::adaptive::OffenseTracker;
+use crate::analyzer::baseline::Baseline;
+use crate::analyzer::custom_rules::{self, RuleConfig};
+use crate::analyzer::git::GitInspector;
+use crate::analyzer::messages::config_reload;
 use crate::app_state::SharedAppState;
 use crate::cli::MergedConfig;
+use crate::config::layers::{self, user_config_path};
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Result, Watcher};
 use regex::Regex;
-use std::path::Path;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::sync::{
     Arc,
@@ -13,18 +20,79 @@ use std::sync::{
 };
 use std::time::{Duration, Instant};
 
+/// Settings of the watcher and the state derived from them. Replaced as a whole when the
+/// config changes, so an analysis never sees a mix of old and new settings.
+struct Settings {
+    config: MergedConfig,
+    /// Custom rules, loaded and compiled once per reload
+    rules: Vec<RuleConfig>,
+    baseline: Option<Baseline>,
+    offenses: Option<OffenseTracker>,
+    /// Config and rules files whose changes trigger a reload
+    reloaded_files: Vec<PathBuf>,
+}
+
+impl Settings {
+    fn new(config: MergedConfig, rules: Vec<RuleConfig>) -> Self {
+        let baseline = config.baseline_file.as_deref().and_then(|path| {
+            match Baseline::from_file(Path::new(path)) {
+                Ok(baseline) => Some(baseline),
+                Err(e) => {
+                    error!("Baseline not used: {}", e);
+                    None
+                }
+            }
+        });
+
+        let offenses = config.adaptive_grumpiness.then(|| {
+            OffenseTracker::from_file(Path::new(&config.offenses_file)).unwrap_or_else(|e| {
+                error!("Offenses forgotten: {}", e);
+                OffenseTracker::default()
+            })
+        });
+
+        Settings {
+            reloaded_files: reloaded_files(&config),
+            config,
+            rules,
+            baseline,
+            offenses,
+        }
+    }
+
+    /// Reads the settings again, keeping the current ones if the new config or its rules
+    /// are invalid. Returns what Clippy has to say about it.
+    fn reload(&mut self, reload: fn() -> std::result::Result<MergedConfig, String>) -> String {
+        let level = &self.config.grumpiness_level;
+        let config = match reload() {
+            Ok(config) => config,
+            Err(e) => return config_reload::invalid(level, &e),
+        };
+        let rules = match custom_rules::load_custom_rules_from_toml(&config.custom_rules) {
+            Ok(rules) => rules.unwrap_or_default(),
+            Err(e) => return config_reload::invalid_rules(level, &e),
+        };
+        *self = Settings::new(config, rules);
+        config_reload::applied(&self.config.grumpiness_level)
+    }
+}
+
+/// Watches the sources (and, with git integration, the commit message) and reports findings
+/// to the GUI. Changes of the config and rules files are picked up by calling `reload`.
 pub fn start_watching(
-    config: &MergedConfig,
+    config: MergedConfig,
+    reload: fn() -> std::result::Result<MergedConfig, String>,
     running: &Arc<AtomicBool>,
     shared_state: SharedAppState,
 ) -> Result<()> {
     let (tx, rx) = channel::<Event>();
-    let watch_extensions = config.watch_files.clone();
-    let ignore_list = config.ignore_patterns.clone();
-    let grumpiness_level = config.grumpiness_level.clone();
-    let max_cyclomatic_complexity = config.max_complexity.clone();
-    let max_function_size = config.max_function_size.clone();
-    let custom_rules_file = config.custom_rules.clone();
+    let rules = custom_rules::load_custom_rules_from_toml(&config.custom_rules)
+        .unwrap_or_else(|e| {
+            error!("Custom rules not checked: {}", e);
+            None
+        })
+        .unwrap_or_default();
+    let mut settings = Settings::new(config, rules);
 
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<Event>| {
@@ -40,6 +108,30 @@ pub fn start_watching(
     )?;
 
     watcher.watch(Path::new("src"), RecursiveMode::Recursive)?;
+    // Config files may live next to other files in the project root and its parents, whose
+    // changes must not be analyzed
+    let sources = normalized(Path::new("src/"));
+    let mut watched_dirs = HashSet::new();
+    watch_dirs_of(&mut watcher, &settings.reloaded_files, &mut watched_dirs);
+
+    // Kept for the whole session so blames are cached between saves
+    let inspector = match GitInspector::new(".") {
+        Ok(inspector) => Some(inspector),
+        Err(e) => {
+            if settings.config.git_integration {
+                error!("Commit messages not watched: {}", e);
+            }
+            None
+        }
+    };
+
+    let mut git_dir_watched = false;
+    watch_git_dir(
+        &mut watcher,
+        inspector.as_ref(),
+        settings.config.git_integration,
+        &mut git_dir_watched,
+    );
 
     let mut last_triggered = Instant::now() - Duration::from_secs(10);
     let debounce_interval = Duration::from_secs(10);
@@ -47,25 +139,66 @@ pub fn start_watching(
     while running.load(Ordering::SeqCst) {
         if let Ok(event) = rx.recv_timeout(Duration::from_secs(1)) {
             if let Some(path) = event.paths.first() {
-                if shall_be_ignored(path, &ignore_list) {
+                if is_reloaded(path, &settings.reloaded_files) {
+                    if event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove() {
+                        let message = settings.reload(reload);
+                        watch_dirs_of(&mut watcher, &settings.reloaded_files, &mut watched_dirs);
+                        watch_git_dir(
+                            &mut watcher,
+                            inspector.as_ref(),
+                            settings.config.git_integration,
+                            &mut git_dir_watched,
+                        );
+                        let mut state = shared_state.write().unwrap();
+                        state.message = message;
+                        state.path = None;
+                        state.findings = vec![];
+                        state.custom_rules = settings.config.custom_rules.clone();
+                    }
+                    continue;
+                }
+                if is_head_or_index(path) {
+                    if let Some(inspector) = &inspector {
+                        inspector.invalidate_cache();
+                    }
+                    continue;
+                }
+                if is_commit_message(path) {
+                    if settings.config.git_integration
+                        && (event.kind.is_modify() || event.kind.is_create())
+                    {
+                        let (message, findings) = handle_commit_message(path, &settings.config);
+                        let mut state = shared_state.write().unwrap();
+                        state.message = message;
+                        state.path = None;
+                        state.findings = findings;
+                    }
+                    continue;
+                }
+                if !is_source(path, &sources)
+                    || shall_be_ignored(path, &settings.config.ignore_patterns)
+                {
                     continue;
                 }
 
-                if is_relevant(path, &watch_extensions) {
+                if is_relevant(path, &settings.config.watch_files) {
                     let now = Instant::now();
                     if now.duration_since(last_triggered) >= debounce_interval {
-                        let message = handle_file_changes(
+                        let (message, findings) = handle_file_changes(
                             path,
-                            &grumpiness_level,
-                            &max_cyclomatic_complexity,
-                            &max_function_size,
-                            Path::new(&custom_rules_file),
+                            &settings.config,
+                            &settings.rules,
+                            settings.baseline.as_ref(),
+                            inspector.as_ref(),
+                            settings.offenses.as_mut(),
                         );
 
                         // Update UI message
                         {
                             let mut state = shared_state.write().unwrap();
                             state.message = message;
+                            state.path = Some(path.clone());
+                            state.findings = findings;
                         }
 
                         last_triggered = now;
@@ -78,6 +211,98 @@ pub fn start_watching(
     Ok(())
 }
 
+/// Config layers (whether they exist yet or not) and the custom rules file of `config`.
+fn reloaded_files(config: &MergedConfig) -> Vec<PathBuf> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    layers::config_files(
+        &cwd,
+        user_config_path().as_deref(),
+        config.config_file.as_deref().map(Path::new),
+    )
+    .into_iter()
+    .chain([PathBuf::from(&config.custom_rules)])
+    .map(|path| normalized(&path))
+    .collect()
+}
+
+/// Watches the directories of `files` that exist and are not watched yet. Directories are
+/// watched instead of the files, as editors often save by replacing the file.
+fn watch_dirs_of(
+    watcher: &mut RecommendedWatcher,
+    files: &[PathBuf],
+    watched_dirs: &mut HashSet<PathBuf>,
+) {
+    for dir in files.iter().filter_map(|file| file.parent()) {
+        if dir.is_dir() && !watched_dirs.contains(dir) {
+            match watcher.watch(dir, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    watched_dirs.insert(dir.to_path_buf());
+                }
+                Err(e) => error!("Changes of '{}' not watched: {}", dir.display(), e),
+            }
+        }
+    }
+}
+
+/// Watches git's directory while git integration is on: the message of a commit in progress
+/// is edited in `.git/COMMIT_EDITMSG`, and changes of `HEAD` and the index outdate blames.
+fn watch_git_dir(
+    watcher: &mut RecommendedWatcher,
+    inspector: Option<&GitInspector>,
+    git_integration: bool,
+    watched: &mut bool,
+) {
+    let Some(inspector) = inspector.filter(|_| git_integration != *watched) else {
+        return;
+    };
+    let result = if git_integration {
+        watcher.watch(inspector.git_dir(), RecursiveMode::NonRecursive)
+    } else {
+        watcher.unwatch(inspector.git_dir())
+    };
+    match result {
+        Ok(()) => *watched = git_integration,
+        Err(e) => error!("Commit messages not watched: {}", e),
+    }
+}
+
+/// Absolute form of `path` with the symlinks of its directory resolved, so paths given in
+/// different ways can be compared.
+fn normalized(path: &Path) -> PathBuf {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    dir.join(path.file_name().unwrap_or_default())
+}
+
+/// Check if a file is one of the config or rules files the settings are reloaded from.
+fn is_reloaded(path: &Path, reloaded_files: &[PathBuf]) -> bool {
+    let path = normalized(path);
+    reloaded_files.contains(&path)
+}
+
+/// Check if a file is below the watched sources directory (given by its `normalized` path).
+fn is_source(path: &Path, sources: &Path) -> bool {
+    normalized(path).starts_with(sources)
+}
+
+/// Check if a file is git's `HEAD` or index, whose changes outdate cached blames.
+fn is_head_or_index(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "HEAD" || name == "index")
+        && path
+            .parent()
+            .is_some_and(|dir| dir.join("objects").is_dir())
+}
+
+/// Check if a file is the commit message git lets the user edit.
+fn is_commit_message(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "COMMIT_EDITMSG")
+}
+
 /// Check if a file has an extension matching one of the allowed watch types.
 ///
 /// # Arguments
@@ -99,10 +324,64 @@ fn is_relevant(path: &Path, allowed_extensions: &Vec<String>) -> bool {
 /// # Arguments
 /// * `path` - Path to the changed file
 /// * `ignore_patterns` - patterns to ignore (e.g., `["target/"]`)
-fn shall_be_ignored(path: &Path, ignore_pattern: &Vec<String>) -> bool {
+pub(crate) fn shall_be_ignored(path: &Path, ignore_pattern: &[String]) -> bool {
     ignore_pattern.iter().any(|pattern| {
         return Regex::new(pattern)
             .unwrap()
             .is_match(path.to_str().unwrap_or(""));
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::CliArgs;
+    use argh::FromArgs;
+
+    fn config(args: &[&str]) -> MergedConfig {
+        MergedConfig::from_sources(CliArgs::from_args(&["grumpy_clippy"], args).unwrap(), None)
+    }
+
+    #[test]
+    fn test_invalid_reload_keeps_settings() {
+        let mut settings = Settings::new(config(&["--max-complexity", "7"]), vec![]);
+        assert!(is_reloaded(
+            Path::new("rules.toml"),
+            &settings.reloaded_files
+        ));
+
+        let message = settings.reload(|| Err("max_complexity must be greater than 0".into()));
+        assert!(message.contains("max_complexity must be greater than 0"));
+        assert_eq!(settings.config.max_complexity, 7);
+
+        let message = settings.reload(|| {
+            let broken_rules = std::env::temp_dir().join("grumpy_clippy_broken_rules.toml");
+            fs::write(&broken_rules, "rules = 1\n").unwrap();
+            Ok(config(&[
+                "--max-complexity",
+                "9",
+                "--custom-rules",
+                broken_rules.to_str().unwrap(),
+            ]))
+        });
+        assert!(
+            message.contains("grumpy_clippy_broken_rules.toml"),
+            "{}",
+            message
+        );
+        assert_eq!(settings.config.max_complexity, 7);
+
+        settings.reload(|| Ok(config(&["--max-complexity", "9"])));
+        assert_eq!(settings.config.max_complexity, 9);
+    }
+
+    #[test]
+    fn test_only_sources_are_analyzed() {
+        let sources = normalized(Path::new("src/"));
+        let cwd = std::env::current_dir().unwrap();
+        assert!(is_source(Path::new("src/main.rs"), &sources));
+        assert!(is_source(&cwd.join("src/analyzer/git.rs"), &sources));
+        assert!(!is_source(Path::new("build.rs"), &sources));
+        assert!(!is_source(&cwd.join("build.rs"), &sources));
+    }
+}